@@ -0,0 +1,136 @@
+// Service worker of the offline mode. The page sends the list of assets to
+// precache along with a version, which is a hash of the names of the built
+// files, so every build gets its own cache and older ones are deleted.
+// Cached responses are served first, and other files of the same origin are
+// cached once requested
+
+const PREFIX = "tutorlolv2-";
+
+// Number of assets requested at the same time while precaching
+const CONCURRENCY = 16;
+
+// Files that keep their name across builds, besides the page itself
+const UNVERSIONED = [
+    "/worker_loader.js",
+    "/manifest.webmanifest",
+    "/icon-192.png",
+    "/icon-512.png",
+];
+
+// Name of the cache of the current version, which is looked up again when
+// the browser restarts this worker
+let current = null;
+
+async function currentCache() {
+    current ??= (await caches.keys()).find((key) => key.startsWith(PREFIX)) ?? null;
+    return current;
+}
+
+self.addEventListener("install", () => self.skipWaiting());
+self.addEventListener("activate", (event) => event.waitUntil(self.clients.claim()));
+
+async function precache(version, urls, client) {
+    const name = PREFIX + version;
+    const cache = await caches.open(name);
+    current = name;
+
+    let done = 0;
+    let failed = 0;
+    const queue = [...urls];
+    const next = async () => {
+        while (queue.length > 0) {
+            const url = queue.pop();
+            try {
+                if (!(await cache.match(url))) {
+                    const response = await fetch(url);
+                    if (!response.ok) {
+                        throw new Error(response.statusText);
+                    }
+                    await cache.put(url, response);
+                }
+            } catch {
+                failed += 1;
+            }
+            done += 1;
+            if (done % 64 === 0) {
+                client?.postMessage({ type: "progress", done, total: urls.length });
+            }
+        }
+    };
+    await Promise.all(Array.from({ length: CONCURRENCY }, next));
+
+    for (const key of await caches.keys()) {
+        if (key.startsWith(PREFIX) && key !== name) {
+            await caches.delete(key);
+        }
+    }
+    client?.postMessage({ type: "ready", version, failed });
+}
+
+async function clear(client) {
+    current = null;
+    for (const key of await caches.keys()) {
+        if (key.startsWith(PREFIX)) {
+            await caches.delete(key);
+        }
+    }
+    await self.registration.unregister();
+    client?.postMessage({ type: "cleared" });
+}
+
+self.addEventListener("message", (event) => {
+    const message = event.data;
+    switch (message?.type) {
+        case "precache":
+            event.waitUntil(precache(message.version, message.urls, event.source));
+            break;
+        case "clear":
+            event.waitUntil(clear(event.source));
+            break;
+    }
+});
+
+// Saves a response of the same origin in the cache of the current version,
+// if the offline mode is enabled
+async function store(request, response) {
+    const name = await currentCache();
+    if (response.ok && name && new URL(request.url).origin === self.location.origin) {
+        const cache = await caches.open(name);
+        await cache.put(request, response.clone());
+    }
+    return response;
+}
+
+// Whether `request` is for a file whose contents can change without its name
+// changing, such as the page, the manifest or the worker loader
+function isUnversioned(request) {
+    const url = new URL(request.url);
+    return request.mode === "navigate"
+        || (url.origin === self.location.origin
+            && UNVERSIONED.some((path) => url.pathname.endsWith(path)));
+}
+
+async function respond(request) {
+    const cached = await caches.match(request, { ignoreSearch: request.mode === "navigate" });
+    // Unversioned files are requested from the network first, so new releases
+    // are loaded as soon as the network is available
+    if (isUnversioned(request)) {
+        try {
+            return await store(request, await fetch(request));
+        } catch (error) {
+            if (cached) {
+                return cached;
+            }
+            throw error;
+        }
+    }
+    return cached ?? store(request, await fetch(request));
+}
+
+self.addEventListener("fetch", (event) => {
+    // Calculations are posted to the server, and are never cached
+    if (event.request.method !== "GET") {
+        return;
+    }
+    event.respondWith(respond(event.request));
+});
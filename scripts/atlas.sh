@@ -0,0 +1,31 @@
+#!/bin/sh
+# Builds the sprite sheets of the atlas image mode from the icons of the
+# server, following the layout printed by `src/bin/atlas.rs`. Sheets are
+# written to `<img dir>/atlas/<category>.avif`, which is where the app
+# requests them. Requires ImageMagick 7
+#
+# Usage: scripts/atlas.sh <img dir> [cell size in pixels]
+set -eu
+
+img_dir=${1:?"Usage: scripts/atlas.sh <img dir> [cell size in pixels]"}
+size=${2:-64}
+# Must match `ATLAS_COLUMNS` of `src/utils/images.rs`
+columns=32
+
+mkdir -p "$img_dir/atlas"
+for category in abilities champions items runes; do
+    list=$(mktemp)
+    # Empty cells and missing icons are transparent, so every other cell
+    # stays in its place
+    cargo run --quiet --bin atlas -- "$category" | while read -r path; do
+        if [ "$path" != "-" ] && [ -f "$img_dir/$path" ]; then
+            echo "$img_dir/$path"
+        else
+            echo "xc:none"
+        fi
+    done >"$list"
+    magick montage @"$list" -tile "${columns}x" -geometry "${size}x${size}+0+0" \
+        -background none "$img_dir/atlas/$category.avif"
+    rm "$list"
+    echo "Built $img_dir/atlas/$category.avif"
+done
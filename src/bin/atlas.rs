@@ -0,0 +1,27 @@
+//! Prints the layout of the sprite sheet of a category, used by
+//! `scripts/atlas.sh` to build the sheets of the atlas image mode. Each line
+//! is a cell, holding the path of its icon relative to the `img` folder of
+//! the server, or `-` if the cell is empty
+use tutorlolv2_web::utils::{
+    BASE_URL,
+    images::{ImageCategory, atlas_layout},
+};
+
+fn main() {
+    let name = std::env::args().nth(1).unwrap_or_default();
+    let Some(category) = ImageCategory::ALL
+        .into_iter()
+        .find(|category| category.name() == name)
+    else {
+        let names = ImageCategory::ALL.map(ImageCategory::name);
+        eprintln!("Usage: atlas <{}>", names.join("|"));
+        std::process::exit(1);
+    };
+    let prefix = format!("{BASE_URL}/img/");
+    for cell in atlas_layout(category) {
+        match cell {
+            Some(image) => println!("{}", image.url().trim_start_matches(&prefix)),
+            None => println!("-"),
+        }
+    }
+}
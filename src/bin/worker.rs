@@ -0,0 +1,3 @@
+fn main() {
+    tutorlolv2_web::worker::runner::run_worker();
+}
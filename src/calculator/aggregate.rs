@@ -0,0 +1,74 @@
+use crate::{
+    calculator::{FinalEnemy, columns::Column},
+    model::Damages,
+};
+
+/// A column of the damage table that is used some number of times in a combo
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ComboEntry {
+    pub column: Column,
+    pub count: u32,
+}
+
+/// Which value of a damage range is used when ranges are summed
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RangeMode {
+    Minimum,
+    #[default]
+    Maximum,
+    Average,
+}
+
+impl RangeMode {
+    pub const ALL: [Self; 3] = [Self::Minimum, Self::Maximum, Self::Average];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Minimum => "Min",
+            Self::Maximum => "Max",
+            Self::Average => "Avg",
+        }
+    }
+
+    pub const fn pick(self, (minimum, maximum): (i32, i32)) -> i32 {
+        match self {
+            Self::Minimum => minimum,
+            Self::Maximum => maximum,
+            Self::Average => (minimum + maximum) / 2,
+        }
+    }
+}
+
+/// Damage of a single column against one enemy, picking a value of the range by `mode`
+pub fn column_damage(column: &Column, damages: &Damages, mode: RangeMode) -> i32 {
+    mode.pick(column.range(damages))
+}
+
+/// Sums the damage range of `column` across every enemy for which `is_hit` returns true
+pub fn aoe_damage(
+    column: &Column,
+    enemies: &[FinalEnemy],
+    is_hit: impl Fn(usize) -> bool,
+) -> (i32, i32) {
+    enemies
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| is_hit(*i))
+        .map(|(_, enemy)| column.range(&enemy.damages))
+        .fold((0, 0), |(min, max), (a, b)| (min + a, max + b))
+}
+
+/// Damage of a full combo against one enemy. Entries for which `is_hit` returns
+/// false are skipped, so abilities that do not reach this enemy are not counted
+pub fn combo_damage(
+    combo: &[ComboEntry],
+    damages: &Damages,
+    mode: RangeMode,
+    is_hit: impl Fn(&Column) -> bool,
+) -> i32 {
+    combo
+        .iter()
+        .filter(|entry| is_hit(&entry.column))
+        .map(|entry| column_damage(&entry.column, damages, mode) * entry.count as i32)
+        .sum()
+}
@@ -0,0 +1,237 @@
+use crate::{
+    model::Damages,
+    utils::{AbilityKind, ImageType},
+};
+use tutorlolv2_gen::{AbilityId, ChampionId, DamageType, ItemId, MergeData, RuneId, TypeMetadata};
+
+/// Groups in which the columns of the damage table are organized
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnGroup {
+    Attacks,
+    Abilities,
+    Items,
+    Runes,
+}
+
+impl ColumnGroup {
+    pub const ALL: [Self; 4] = [Self::Attacks, Self::Abilities, Self::Items, Self::Runes];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Attacks => "Attacks",
+            Self::Abilities => "Abilities",
+            Self::Items => "Items",
+            Self::Runes => "Runes",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|group| group.name() == name)
+    }
+}
+
+/// A single column of the damage table. The `usize` field is the index of
+/// this column in the respective slice of [`Damages`]. Abilities that were merged
+/// are represented by [`AbilityKind::Alias`], whose indexes point to the minimum
+/// and maximum damage values instead
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Column {
+    BasicAttack,
+    CritStrike,
+    /// Damage applied by every basic attack, from [`Attacks::onhit_damage`]
+    ///
+    /// [`Attacks::onhit_damage`]: crate::model::Attacks::onhit_damage
+    Onhit,
+    Ability(usize, AbilityKind),
+    Item(usize, ItemId),
+    Rune(usize, RuneId),
+}
+
+impl Column {
+    pub const fn group(&self) -> ColumnGroup {
+        match self {
+            Column::BasicAttack | Column::CritStrike | Column::Onhit => ColumnGroup::Attacks,
+            Column::Ability(..) => ColumnGroup::Abilities,
+            Column::Item(..) => ColumnGroup::Items,
+            Column::Rune(..) => ColumnGroup::Runes,
+        }
+    }
+
+    pub fn image_type(&self, champion_id: ChampionId) -> ImageType {
+        match *self {
+            Column::BasicAttack | Column::Onhit => ImageType::BasicAttack,
+            Column::CritStrike => ImageType::CritStrike,
+            Column::Ability(_, kind) => ImageType::Ability(champion_id, kind),
+            Column::Item(_, item_id) => ImageType::Item(item_id),
+            Column::Rune(_, rune_id) => ImageType::Rune(rune_id),
+        }
+    }
+
+    /// Human readable name of this column, such as `Q` or `Q Empowered`
+    pub fn label(&self) -> String {
+        match self {
+            Column::BasicAttack => String::from("Basic Attack"),
+            Column::CritStrike => String::from("Critical Strike"),
+            Column::Onhit => String::from("Onhit"),
+            Column::Ability(_, kind) => {
+                let ability_id = kind.ability_id();
+                match ability_id.ability_name().display() {
+                    Some(name) => format!("{} {name}", ability_id.as_char()),
+                    None => ability_id.as_char().to_string(),
+                }
+            }
+            Column::Item(_, item_id) => item_id.name().to_string(),
+            Column::Rune(_, rune_id) => rune_id.name().to_string(),
+        }
+    }
+
+    /// Returns the damage type of this column, as described by its [`TypeMetadata`].
+    /// Basic attacks and critical strikes always deal physical damage, while onhit
+    /// damage adds up effects of any damage type
+    pub fn damage_type(
+        &self,
+        abilities_meta: &[TypeMetadata<AbilityId>],
+        items_meta: &[TypeMetadata<ItemId>],
+        runes_meta: &[TypeMetadata<RuneId>],
+    ) -> DamageType {
+        let index = match *self {
+            Column::Ability(_, AbilityKind::Alias(merge)) => merge.minimum_damage as usize,
+            Column::Ability(index, _) | Column::Item(index, _) | Column::Rune(index, _) => index,
+            Column::BasicAttack | Column::CritStrike => return DamageType::Physical,
+            Column::Onhit => return DamageType::Mixed,
+        };
+        let damage_type = match self.group() {
+            ColumnGroup::Abilities => abilities_meta.get(index).map(|m| m.damage_type),
+            ColumnGroup::Items => items_meta.get(index).map(|m| m.damage_type),
+            ColumnGroup::Runes => runes_meta.get(index).map(|m| m.damage_type),
+            ColumnGroup::Attacks => None,
+        };
+        damage_type.unwrap_or(DamageType::Unknown)
+    }
+
+    /// Returns the minimum and maximum damage of this column. Columns that
+    /// do not represent a range return the same value twice
+    pub fn range(&self, damages: &Damages) -> (i32, i32) {
+        let get = |slice: &[i32], index: usize| slice.get(index).copied().unwrap_or_default();
+        let single = |value| (value, value);
+        match *self {
+            Column::BasicAttack => single(damages.attacks.basic_attack),
+            Column::CritStrike => single(damages.attacks.critical_strike),
+            Column::Onhit => {
+                let onhit = damages.attacks.onhit_damage;
+                (onhit.minimum_damage, onhit.maximum_damage)
+            }
+            Column::Ability(_, AbilityKind::Alias(merge)) => (
+                get(&damages.abilities, merge.minimum_damage as usize),
+                get(&damages.abilities, merge.maximum_damage as usize),
+            ),
+            Column::Ability(index, AbilityKind::Normal(_)) => {
+                single(get(&damages.abilities, index))
+            }
+            Column::Item(index, _) => single(get(&damages.items, index)),
+            Column::Rune(index, _) => single(get(&damages.runes, index)),
+        }
+    }
+}
+
+/// Returns every ability column in display order. Abilities whose index is the
+/// `maximum_damage` of some [`MergeData`] are skipped, since they are displayed
+/// together with the ability at index `minimum_damage`
+pub fn ability_columns(
+    abilities_meta: &[TypeMetadata<AbilityId>],
+    abilities_to_merge: &[MergeData],
+) -> Vec<Column> {
+    let meta_len = abilities_meta.len();
+    let merge_len = abilities_to_merge.len();
+    let mut result = Vec::with_capacity(meta_len.saturating_sub(merge_len));
+
+    let mut i = 0;
+    'outer: while i < meta_len {
+        let metadata = abilities_meta[i];
+        let mut ability_kind = AbilityKind::Normal(metadata.kind);
+        let mut j = 0;
+        'inner: while j < merge_len {
+            let merge = abilities_to_merge[j];
+            j += 1;
+            if merge.maximum_damage == i as u8 {
+                i += 1;
+                continue 'outer;
+            }
+            if merge.minimum_damage == i as u8 {
+                ability_kind = AbilityKind::Alias(merge);
+                break 'inner;
+            }
+        }
+
+        result.push(Column::Ability(i, ability_kind));
+        i += 1;
+    }
+
+    result
+}
+
+/// Returns all columns of the damage table, in the same order they are displayed
+pub fn columns(
+    abilities_meta: &[TypeMetadata<AbilityId>],
+    abilities_to_merge: &[MergeData],
+    items_meta: &[TypeMetadata<ItemId>],
+    runes_meta: &[TypeMetadata<RuneId>],
+) -> Vec<Column> {
+    let mut result = vec![Column::BasicAttack, Column::CritStrike, Column::Onhit];
+    result.extend(ability_columns(abilities_meta, abilities_to_merge));
+    result.extend(
+        items_meta
+            .iter()
+            .enumerate()
+            .map(|(i, metadata)| Column::Item(i, metadata.kind)),
+    );
+    result.extend(
+        runes_meta
+            .iter()
+            .enumerate()
+            .map(|(i, metadata)| Column::Rune(i, metadata.kind)),
+    );
+    result
+}
+
+/// Tailwind classes used to color the text and border of cells of some [`DamageType`]
+pub const fn damage_classes(damage_type: DamageType) -> (&'static str, &'static str) {
+    match damage_type {
+        DamageType::Physical => ("text-orange-400", "border-orange-400"),
+        DamageType::Magic => ("text-sky-400", "border-sky-400"),
+        DamageType::True => ("text-white", "border-white"),
+        DamageType::Mixed => ("text-fuchsia-400", "border-fuchsia-400"),
+        _ => ("text-std-300", "border-std-600"),
+    }
+}
+
+/// Total damage against one enemy, split by [`DamageType`]. Columns of
+/// other damage types, such as adaptative ones, are added to `mixed`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DamageBreakdown {
+    pub physical: i32,
+    pub magic: i32,
+    pub true_damage: i32,
+    pub mixed: i32,
+}
+
+impl DamageBreakdown {
+    pub fn add(&mut self, damage_type: DamageType, value: i32) {
+        match damage_type {
+            DamageType::Physical => self.physical += value,
+            DamageType::Magic => self.magic += value,
+            DamageType::True => self.true_damage += value,
+            _ => self.mixed += value,
+        }
+    }
+
+    /// Pairs each total with its [`DamageType`] and label, in display order
+    pub const fn parts(&self) -> [(DamageType, &'static str, i32); 4] {
+        [
+            (DamageType::Physical, "Physical", self.physical),
+            (DamageType::Magic, "Magic", self.magic),
+            (DamageType::True, "True", self.true_damage),
+            (DamageType::Mixed, "Mixed", self.mixed),
+        ]
+    }
+}
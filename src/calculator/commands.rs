@@ -0,0 +1,249 @@
+//! Commands of the command palette. Each [`Command`] is parsed from free text
+//! such as `add item rabadon` or `set q 5`, matching both the command and the
+//! name of its argument with [`fuzzy_score`]
+use crate::{
+    calculator::{AbilitySlot, sweep::MAX_LEVEL},
+    data::{items::all_items, runes::all_runes},
+    utils::{EnumCast, fuzzy::fuzzy_score, variants},
+};
+use bincode::{Decode, Encode};
+use tutorlolv2_gen::{ChampionId, ItemId, RuneId};
+
+/// Maximum number of suggestions listed by the palette
+pub const MAX_SUGGESTIONS: usize = 10;
+
+/// Maximum number of entities suggested for each command
+const MAX_MATCHES: usize = 5;
+
+#[derive(Clone, Copy, Debug, Decode, Encode, PartialEq)]
+pub enum DragonKind {
+    AllyFire,
+    AllyEarth,
+    AllyChemtech,
+    EnemyEarth,
+}
+
+#[derive(Clone, Copy, Debug, Decode, Encode, PartialEq)]
+pub enum Command {
+    SetChampion(ChampionId),
+    AddItem(ItemId),
+    RemoveItem(ItemId),
+    AddRune(RuneId),
+    RemoveRune(RuneId),
+    SetLevel(u8),
+    SetAbility(AbilitySlot, u8),
+    SetStacks(u32),
+    ToggleInferStats,
+    ToggleMegaGnar,
+    AddEnemy(ChampionId),
+    RemoveEnemy(ChampionId),
+    SetDragons(DragonKind, u16),
+}
+
+/// Kind of the argument that follows the name of a command
+#[derive(Clone, Copy)]
+enum Argument {
+    None(Command),
+    Number(fn(u32) -> Command),
+    Champion(fn(ChampionId) -> Command),
+    Item(fn(ItemId) -> Command),
+    Rune(fn(RuneId) -> Command),
+}
+
+const COMMANDS: &[(&str, Argument)] = &[
+    ("set champion", Argument::Champion(Command::SetChampion)),
+    ("add item", Argument::Item(Command::AddItem)),
+    ("remove item", Argument::Item(Command::RemoveItem)),
+    ("add rune", Argument::Rune(Command::AddRune)),
+    ("remove rune", Argument::Rune(Command::RemoveRune)),
+    (
+        "set level",
+        Argument::Number(|v| Command::SetLevel(v.clamp(1, MAX_LEVEL as u32) as u8)),
+    ),
+    ("set q", Argument::Number(|v| ability(AbilitySlot::Q, v))),
+    ("set w", Argument::Number(|v| ability(AbilitySlot::W, v))),
+    ("set e", Argument::Number(|v| ability(AbilitySlot::E, v))),
+    ("set r", Argument::Number(|v| ability(AbilitySlot::R, v))),
+    ("set stacks", Argument::Number(Command::SetStacks)),
+    (
+        "toggle infer stats",
+        Argument::None(Command::ToggleInferStats),
+    ),
+    ("toggle mega gnar", Argument::None(Command::ToggleMegaGnar)),
+    ("add enemy", Argument::Champion(Command::AddEnemy)),
+    ("remove enemy", Argument::Champion(Command::RemoveEnemy)),
+    (
+        "set fire dragons",
+        Argument::Number(|v| dragons(DragonKind::AllyFire, v)),
+    ),
+    (
+        "set earth dragons",
+        Argument::Number(|v| dragons(DragonKind::AllyEarth, v)),
+    ),
+    (
+        "set chemtech dragons",
+        Argument::Number(|v| dragons(DragonKind::AllyChemtech, v)),
+    ),
+    (
+        "set enemy earth dragons",
+        Argument::Number(|v| dragons(DragonKind::EnemyEarth, v)),
+    ),
+];
+
+fn ability(slot: AbilitySlot, value: u32) -> Command {
+    Command::SetAbility(slot, value.min(slot.max_rank() as u32) as u8)
+}
+
+fn dragons(kind: DragonKind, value: u32) -> Command {
+    Command::SetDragons(kind, value.min(u16::MAX as u32) as u16)
+}
+
+/// Entry of the palette. Suggestions without a command are incomplete, and
+/// only show the expected argument
+#[derive(Clone, Debug, Decode, Encode, PartialEq)]
+pub struct Suggestion {
+    pub label: String,
+    pub command: Option<Command>,
+    score: i32,
+}
+
+/// Variants of `T` whose name matches `query`, from best to worst match
+fn best_matches<T: EnumCast>(options: impl Iterator<Item = T>, query: &str) -> Vec<(i32, T)> {
+    let mut matches = options
+        .filter_map(|option| Some((fuzzy_score(query, option.name())?, option)))
+        .collect::<Vec<_>>();
+    matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+    matches.truncate(MAX_MATCHES);
+    matches
+}
+
+fn entity_suggestions<T: EnumCast>(
+    name: &str,
+    score: i32,
+    options: impl Iterator<Item = T>,
+    rest: &str,
+    command: fn(T) -> Command,
+) -> Vec<Suggestion> {
+    match rest.is_empty() {
+        true => vec![Suggestion {
+            label: format!("{name} …"),
+            command: None,
+            score,
+        }],
+        false => best_matches(options, rest)
+            .into_iter()
+            .map(|(entity_score, entity)| Suggestion {
+                label: format!("{name} {}", entity.name()),
+                command: Some(command(entity)),
+                score: score + entity_score,
+            })
+            .collect(),
+    }
+}
+
+fn suggestions_for(name: &str, argument: Argument, score: i32, rest: &str) -> Vec<Suggestion> {
+    let rest = rest.trim();
+    match argument {
+        Argument::None(command) => match rest.is_empty() {
+            true => vec![Suggestion {
+                label: name.to_string(),
+                command: Some(command),
+                score,
+            }],
+            false => Vec::new(),
+        },
+        Argument::Number(command) => match rest.parse::<u32>() {
+            Ok(value) => vec![Suggestion {
+                label: format!("{name} {value}"),
+                command: Some(command(value)),
+                score,
+            }],
+            Err(_) if rest.is_empty() => vec![Suggestion {
+                label: format!("{name} <number>"),
+                command: None,
+                score,
+            }],
+            Err(_) => Vec::new(),
+        },
+        Argument::Champion(command) => {
+            entity_suggestions(name, score, variants::<ChampionId>(), rest, command)
+        }
+        Argument::Item(command) => entity_suggestions(name, score, all_items(), rest, command),
+        Argument::Rune(command) => entity_suggestions(name, score, all_runes(), rest, command),
+    }
+}
+
+/// Lists the commands matching `query`. The query is split after each of its
+/// words, and the first part is matched against the name of every command
+/// while the rest is parsed as its argument. Every champion, item and rune is
+/// scored for each split, so the palette runs this in the worker
+pub fn suggestions(query: &str) -> Vec<Suggestion> {
+    let words = query.split_whitespace().collect::<Vec<_>>();
+    if words.is_empty() {
+        return COMMANDS
+            .iter()
+            .flat_map(|&(name, argument)| suggestions_for(name, argument, 0, ""))
+            .take(MAX_SUGGESTIONS)
+            .collect();
+    }
+
+    let mut result = Vec::new();
+    for &(name, argument) in COMMANDS {
+        let best = (1..=words.len())
+            .filter_map(|split| {
+                let score = fuzzy_score(&words[..split].join(" "), name)?;
+                Some(suggestions_for(
+                    name,
+                    argument,
+                    score,
+                    &words[split..].join(" "),
+                ))
+            })
+            .filter(|suggestions| !suggestions.is_empty())
+            .max_by_key(|suggestions| suggestions.iter().map(|s| s.score).max());
+        result.extend(best.unwrap_or_default());
+    }
+    result.sort_by(|a, b| b.score.cmp(&a.score));
+    result.truncate(MAX_SUGGESTIONS);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_command(query: &str) -> Option<Command> {
+        suggestions(query).first()?.command
+    }
+
+    #[test]
+    fn clamps_numbers_to_their_range() {
+        let max_rank = AbilitySlot::Q.max_rank();
+        assert_eq!(
+            first_command("set q 9"),
+            Some(Command::SetAbility(AbilitySlot::Q, max_rank))
+        );
+        assert_eq!(
+            first_command("set level 40"),
+            Some(Command::SetLevel(MAX_LEVEL))
+        );
+        assert_eq!(first_command("set level 0"), Some(Command::SetLevel(1)));
+    }
+
+    #[test]
+    fn resolves_entities_by_name() {
+        let Some(Command::AddItem(item_id)) = first_command("add item rabadon") else {
+            panic!("{:#?}", suggestions("add item rabadon"));
+        };
+        assert!(item_id.name().contains("Rabadon"), "{}", item_id.name());
+    }
+
+    #[test]
+    fn rejects_arguments_of_commands_without_one() {
+        assert!(suggestions("toggle infer stats x").is_empty());
+        assert_eq!(
+            first_command("toggle infer stats"),
+            Some(Command::ToggleInferStats)
+        );
+    }
+}
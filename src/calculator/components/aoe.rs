@@ -0,0 +1,215 @@
+use crate::{
+    calculator::{
+        Game,
+        aggregate::{ComboEntry, RangeMode, aoe_damage, combo_damage},
+        columns::{Column, columns},
+        reducer::EnemyId,
+    },
+    components::{
+        image::Image,
+        range::{RangeValue, format_range},
+    },
+    utils::ImageType,
+};
+use std::rc::Rc;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct AoePanelProps {
+    pub game: Rc<Game>,
+    /// Identifiers of the enemies of `game`, in the same order
+    pub enemy_ids: Rc<[EnemyId]>,
+}
+
+/// Sums the damage of each column across the enemies it hits, and the damage of
+/// a combo across the whole enemy team. Every enemy is hit by default, and clicking
+/// an enemy in the row of some column toggles whether that column reaches it
+#[component]
+pub fn AoePanel(props: &AoePanelProps) -> Html {
+    let AoePanelProps { game, enemy_ids } = props;
+
+    // Enemies are identified by id, so a missed hit stays with its champion
+    // when the enemies are reordered, and is dropped once it is removed
+    let missed = use_state(Vec::<(Column, EnemyId)>::new);
+    let combo = use_state(Vec::<ComboEntry>::new);
+    let mode = use_state(RangeMode::default);
+
+    {
+        let missed = missed.clone();
+        use_effect_with(enemy_ids.clone(), move |enemy_ids| {
+            if missed.iter().any(|(_, id)| !enemy_ids.contains(id)) {
+                missed.set(
+                    missed
+                        .iter()
+                        .copied()
+                        .filter(|(_, id)| enemy_ids.contains(id))
+                        .collect(),
+                );
+            }
+        });
+    }
+
+    let champion_id = game.current_player.champion_id;
+    let columns = columns(
+        &game.abilities_meta,
+        &game.abilities_to_merge,
+        &game.items_meta,
+        &game.runes_meta,
+    );
+
+    let is_hit = |column: Column, index: usize| {
+        enemy_ids
+            .get(index)
+            .is_none_or(|id| !missed.contains(&(column, *id)))
+    };
+    let sums = columns
+        .iter()
+        .map(|column| aoe_damage(column, &game.enemies, |i| is_hit(*column, i)))
+        .collect::<Vec<_>>();
+    let scale = sums.iter().map(|(_, maximum)| *maximum).max();
+
+    let rows = columns
+        .iter()
+        .zip(sums.iter())
+        .map(|(&column, &(minimum, maximum))| {
+            let toggles = game
+                .enemies
+                .iter()
+                .enumerate()
+                .map(|(i, enemy)| {
+                    let hit = is_hit(column, i);
+                    let id = enemy_ids.get(i).copied();
+                    let (low, high) = column.range(&enemy.damages);
+                    let title = format_range(low, high);
+                    let onclick = {
+                        let missed = missed.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let Some(id) = id else {
+                                return;
+                            };
+                            let mut new = (*missed).clone();
+                            match new.iter().position(|pair| *pair == (column, id)) {
+                                Some(position) => {
+                                    new.remove(position);
+                                }
+                                None => new.push((column, id)),
+                            }
+                            missed.set(new);
+                        })
+                    };
+                    let mut class = classes!("cursor-pointer");
+                    if !hit {
+                        class.push(classes!("opacity-30", "grayscale"));
+                    }
+                    html! {
+                        <td {class} {onclick} {title}>
+                            <Image src={ImageType::Champion(enemy.champion_id)} />
+                        </td>
+                    }
+                })
+                .collect::<Html>();
+
+            let onadd = {
+                let combo = combo.clone();
+                Callback::from(move |_: MouseEvent| {
+                    let mut new = (*combo).clone();
+                    match new.iter_mut().find(|entry| entry.column == column) {
+                        Some(entry) => entry.count += 1,
+                        None => new.push(ComboEntry { column, count: 1 }),
+                    }
+                    combo.set(new);
+                })
+            };
+
+            html! {
+                <tr>
+                    <td><Image src={column.image_type(champion_id)} focusable={true} /></td>
+                    {toggles}
+                    <td class={classes!("px-2", "w-32")}>
+                        <RangeValue {minimum} {maximum} {scale} />
+                    </td>
+                    <td>
+                        <button class={classes!("px-2", "bg-std-800")} onclick={onadd}>{"+"}</button>
+                    </td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    let combo_entries = combo
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let onclick = {
+                let combo = combo.clone();
+                Callback::from(move |_: MouseEvent| {
+                    let mut new = (*combo).clone();
+                    match new[i].count > 1 {
+                        true => new[i].count -= 1,
+                        false => {
+                            new.remove(i);
+                        }
+                    }
+                    combo.set(new);
+                })
+            };
+            html! {
+                <button class={classes!("flex", "items-center", "gap-1")} {onclick}>
+                    <Image src={entry.column.image_type(champion_id)} />
+                    <span class={classes!("text-sm")}>{format!("×{}", entry.count)}</span>
+                </button>
+            }
+        })
+        .collect::<Html>();
+
+    let per_enemy = game
+        .enemies
+        .iter()
+        .enumerate()
+        .map(|(i, enemy)| {
+            let damage = combo_damage(&combo, &enemy.damages, *mode, |column| is_hit(*column, i));
+            (enemy, damage)
+        })
+        .collect::<Vec<_>>();
+    let teamfight_damage = per_enemy.iter().map(|(_, damage)| damage).sum::<i32>();
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-2")}>
+            <table>
+                <tbody>{rows}</tbody>
+            </table>
+            <div class={classes!("flex", "gap-1", "items-center", "flex-wrap")}>
+                <span>{"Combo"}</span>
+                {RangeMode::ALL.into_iter().map(|value| {
+                    let onclick = {
+                        let mode = mode.clone();
+                        Callback::from(move |_: MouseEvent| mode.set(value))
+                    };
+                    let mut class = classes!("px-2", "text-sm");
+                    class.push(match value == *mode {
+                        true => "bg-std-700",
+                        false => "bg-std-900",
+                    });
+                    html! { <button {class} {onclick}>{value.name()}</button> }
+                }).collect::<Html>()}
+                {combo_entries}
+            </div>
+            <div class={classes!("flex", "gap-4", "items-center", "flex-wrap")}>
+                {per_enemy.iter().map(|(enemy, damage)| {
+                    let health = enemy.current_stats.health;
+                    let mut class = classes!("text-sm");
+                    if *damage >= health {
+                        class.push("text-red-400");
+                    }
+                    html! {
+                        <div class={classes!("flex", "items-center", "gap-1")}>
+                            <Image src={ImageType::Champion(enemy.champion_id)} focusable={true} />
+                            <span {class}>{format!("{damage} / {health}")}</span>
+                        </div>
+                    }
+                }).collect::<Html>()}
+                <span>{format!("Teamfight damage: {teamfight_damage}")}</span>
+            </div>
+        </div>
+    }
+}
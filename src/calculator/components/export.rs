@@ -0,0 +1,80 @@
+use crate::{
+    calculator::{
+        Game,
+        export::{ExportFormat, export, file_name},
+    },
+    utils::{clipboard::copy_text, download::download_text},
+};
+use std::rc::Rc;
+use yew::{platform::spawn_local, prelude::*};
+
+#[derive(PartialEq, Properties)]
+pub struct ExportPanelProps {
+    pub game: Rc<Game>,
+}
+
+/// Downloads or copies the current results in the selected [`ExportFormat`]
+#[component]
+pub fn ExportPanel(props: &ExportPanelProps) -> Html {
+    let ExportPanelProps { game } = props;
+
+    let format = use_state(|| ExportFormat::Csv);
+    let status = use_state(|| None::<String>);
+
+    let on_download = {
+        let game = game.clone();
+        let format = format.clone();
+        let status = status.clone();
+        Callback::from(move |_: MouseEvent| {
+            let content = export(&game, *format);
+            match download_text(&file_name(&game, *format), format.mime(), &content) {
+                Ok(()) => status.set(None),
+                Err(e) => status.set(Some(format!("Failed to download: {e:?}"))),
+            }
+        })
+    };
+
+    let on_copy = {
+        let game = game.clone();
+        let format = format.clone();
+        let status = status.clone();
+        Callback::from(move |_: MouseEvent| {
+            let content = export(&game, *format);
+            let status = status.clone();
+            spawn_local(async move {
+                match copy_text(&content).await {
+                    Ok(()) => status.set(Some(String::from("Copied to clipboard"))),
+                    Err(e) => status.set(Some(format!("Failed to copy: {e:?}"))),
+                }
+            });
+        })
+    };
+
+    let button = classes!("px-2", "py-1", "bg-std-800", "hover:bg-std-700");
+
+    html! {
+        <div class={classes!("flex", "gap-2", "items-center")}>
+            <span>{"Export"}</span>
+            {ExportFormat::ALL.into_iter().map(|value| {
+                let onclick = {
+                    let format = format.clone();
+                    Callback::from(move |_: MouseEvent| format.set(value))
+                };
+                let mut class = classes!("px-2", "text-sm");
+                class.push(match value == *format {
+                    true => "bg-std-700",
+                    false => "bg-std-900",
+                });
+                html! { <button {class} {onclick}>{value.name()}</button> }
+            }).collect::<Html>()}
+            <button class={button.clone()} onclick={on_download}>{"Download"}</button>
+            <button class={button} onclick={on_copy}>{"Copy"}</button>
+            {match *status {
+                Some(ref message) => html! {
+                    <span class={classes!("text-sm", "text-std-400")}>{message}</span>
+                },
+                None => html! {},
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{components::picker::Picker, utils::variants};
+use std::rc::Rc;
+use tutorlolv2_gen::ChampionId;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct ChampionSelectProps {
+    pub value: ChampionId,
+    pub onchange: Callback<ChampionId>,
+}
+
+#[component]
+pub fn ChampionSelect(props: &ChampionSelectProps) -> Html {
+    let ChampionSelectProps { value, onchange } = props;
+
+    let options = use_memo((), |_| variants::<ChampionId>().collect::<Rc<[_]>>());
+
+    html! {
+        <Picker<ChampionId>
+            options={(*options).clone()}
+            value={Some(*value)}
+            onselect={onchange.clone()}
+            placeholder="Champion"
+        />
+    }
+}
@@ -1,14 +1,27 @@
-use crate::calculator::{
-    page::EnemyProps,
-    reducer::{Enemies, EnemyAction, EnemyDataAction, LastAction},
+use crate::{
+    calculator::{
+        components::inputs::{
+            champion::ChampionSelect, items::ItemsInput, mechanic::MechanicInput,
+            stats::StatsSummary,
+        },
+        inferred::InferredStats,
+        page::EnemyProps,
+        reducer::{DataAction, Enemies, EnemyAction, EnemyDataAction},
+    },
+    components::{
+        image::Image,
+        reorder::{moved_index, use_reorder},
+    },
+    model::SimpleStats,
+    utils::ImageType,
 };
-use std::{cell::RefCell, rc::Rc};
 use yew::prelude::*;
 
 #[derive(PartialEq, Properties)]
 pub struct EnemiesInputProps {
     pub enemies: UseReducerHandle<Enemies>,
-    pub last_action: Rc<RefCell<LastAction>>,
+    #[prop_or_default]
+    pub inferred: InferredStats,
 }
 
 #[hook]
@@ -19,31 +32,149 @@ pub fn use_enemy_callback<T: 'static>(
     let EnemyProps {
         enemies,
         enemy_index,
-        last_action,
     } = props.clone();
-    use_callback((), move |v, _| {
-        let index = *enemy_index;
-        let value = callback(v);
-        last_action.replace(value.action(index));
-        enemies.dispatch(EnemyAction::Change(index, value));
+    let id = enemies.id(*enemy_index);
+    use_callback(id, move |v, id| {
+        if let Some(id) = *id {
+            enemies.dispatch(EnemyAction::Change(id, callback(v)));
+        }
     })
 }
 
+#[derive(PartialEq, Properties)]
+pub struct EnemyInputProps {
+    pub enemy_props: EnemyProps,
+    pub inferred: Option<SimpleStats>,
+}
+
 #[component]
-pub fn EnemiesInput(props: &EnemiesInputProps) -> Html {
-    let EnemiesInputProps {
-        enemies,
-        last_action,
+pub fn EnemyInput(props: &EnemyInputProps) -> Html {
+    let EnemyInputProps {
+        enemy_props,
+        inferred,
     } = props;
+    let enemy = &enemy_props.enemies[*enemy_props.enemy_index];
+
+    let set_champion = use_enemy_callback(enemy_props, DataAction::ChampionId);
+    let set_stacks = use_enemy_callback(enemy_props, DataAction::Stacks);
+    let set_form = use_enemy_callback(enemy_props, DataAction::IsMegaGnar);
+    let insert_item = use_enemy_callback(enemy_props, DataAction::InsertItem);
+    let remove_item = use_enemy_callback(enemy_props, DataAction::RemoveItem);
+    let move_item = use_enemy_callback(enemy_props, |(from, to): (usize, usize)| {
+        DataAction::MoveItem(from, to)
+    });
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-2")}>
+            <ChampionSelect value={enemy.champion_id} onchange={set_champion} />
+            <StatsSummary
+                stats={enemy.effective_stats(*inferred)}
+                inferred={enemy.infer_stats && inferred.is_some()}
+            />
+            <MechanicInput
+                champion_id={enemy.champion_id}
+                stacks={enemy.stacks}
+                is_mega_gnar={enemy.is_mega_gnar}
+                onstacks={set_stacks}
+                onform={set_form}
+            />
+            <ItemsInput
+                items={enemy.items.clone()}
+                oninsert={insert_item}
+                onremove={remove_item}
+                onmove={move_item}
+            />
+        </div>
+    }
+}
+
+#[component]
+pub fn EnemiesInput(props: &EnemiesInputProps) -> Html {
+    let EnemiesInputProps { enemies, inferred } = props;
 
     let enemy_index = use_state(|| 0);
+
+    let on_insert = {
+        let enemies = enemies.clone();
+        let enemy_index = enemy_index.clone();
+        Callback::from(move |_: MouseEvent| {
+            enemy_index.set(enemies.len());
+            enemies.dispatch(EnemyAction::Insert(Default::default()));
+        })
+    };
+
+    let on_move = {
+        let enemies = enemies.clone();
+        let enemy_index = enemy_index.clone();
+        Callback::from(move |(from, to): (usize, usize)| {
+            if let Some(id) = enemies.id(from) {
+                enemy_index.set(moved_index(*enemy_index, from, to));
+                enemies.dispatch(EnemyAction::Move(id, to));
+            }
+        })
+    };
+    let reorder = use_reorder(on_move);
+
+    let tabs = enemies
+        .iter()
+        .zip(enemies.ids().iter().copied())
+        .enumerate()
+        .map(|(i, (enemy, id))| {
+            let on_select = {
+                let enemy_index = enemy_index.clone();
+                Callback::from(move |_: MouseEvent| enemy_index.set(i))
+            };
+            let on_remove = {
+                let enemies = enemies.clone();
+                let enemy_index = enemy_index.clone();
+                Callback::from(move |e: MouseEvent| {
+                    e.stop_propagation();
+                    let selected = match i < *enemy_index {
+                        true => *enemy_index - 1,
+                        false => *enemy_index,
+                    };
+                    enemy_index.set(selected.min(enemies.len().saturating_sub(2)));
+                    enemies.dispatch(EnemyAction::Remove(id));
+                })
+            };
+            let mut class = classes!("flex", "items-center", "gap-1", "px-1");
+            if i == *enemy_index {
+                class.push("bg-std-800");
+            }
+            let handlers = reorder.handlers(i);
+            html! {
+                <div
+                    {class}
+                    draggable="true"
+                    onclick={on_select}
+                    ondragstart={handlers.ondragstart}
+                    ondragover={handlers.ondragover}
+                    ondrop={handlers.ondrop}
+                    ondragend={handlers.ondragend}
+                >
+                    <Image src={ImageType::Champion(enemy.champion_id)} />
+                    <button class={classes!("text-sm")} onclick={on_remove}>{"×"}</button>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
     let enemy_props = EnemyProps {
         enemies: enemies.clone(),
-        enemy_index,
-        last_action: last_action.clone(),
+        enemy_index: enemy_index.clone(),
     };
+    let enemy_inferred = enemies.id(*enemy_index).and_then(|id| inferred.enemy(id));
 
     html! {
-        <div></div>
+        <div class={classes!("flex", "flex-col", "gap-2")}>
+            <div class={classes!("flex", "gap-1")}>
+                {tabs}
+                <button class={classes!("px-2", "bg-std-800")} onclick={on_insert}>{"+"}</button>
+            </div>
+            {match *enemy_index < enemies.len() {
+                true => html! { <EnemyInput {enemy_props} inferred={enemy_inferred} /> },
+                false => html! {},
+            }}
+        </div>
     }
 }
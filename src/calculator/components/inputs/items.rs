@@ -0,0 +1,87 @@
+use crate::{
+    components::{image::Image, picker::Picker, reorder::use_reorder},
+    data::items::{all_items, validate_build, validate_insert},
+    utils::ImageType,
+};
+use std::rc::Rc;
+use tutorlolv2_gen::ItemId;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct ItemsInputProps {
+    pub items: Vec<ItemId>,
+    pub oninsert: Callback<ItemId>,
+    pub onremove: Callback<usize>,
+    /// Moves the item at the first index to the second one
+    pub onmove: Callback<(usize, usize)>,
+}
+
+/// Edits the items of a player. Items that can not be added to the current
+/// build are rejected before being dispatched, and the reason is shown below.
+/// Clicking an item removes it, and dragging it changes its slot
+#[component]
+pub fn ItemsInput(props: &ItemsInputProps) -> Html {
+    let ItemsInputProps {
+        items,
+        oninsert,
+        onremove,
+        onmove,
+    } = props;
+
+    let rejected = use_state(|| None::<String>);
+    let reorder = use_reorder(onmove.clone());
+    let options = use_memo((), |_| all_items().collect::<Rc<[_]>>());
+
+    let onselect = {
+        let items = items.clone();
+        let oninsert = oninsert.clone();
+        let rejected = rejected.clone();
+        Callback::from(
+            move |item_id: ItemId| match validate_insert(&items, item_id) {
+                Ok(()) => {
+                    rejected.set(None);
+                    oninsert.emit(item_id);
+                }
+                Err(error) => rejected.set(Some(error.to_string())),
+            },
+        )
+    };
+
+    let errors = validate_build(items)
+        .into_iter()
+        .map(|error| error.to_string())
+        .chain((*rejected).clone());
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-1")}>
+            <div class={classes!("flex", "gap-1", "flex-wrap")}>
+                {items.iter().enumerate().map(|(i, item_id)| {
+                    let onremove = onremove.clone();
+                    let rejected = rejected.clone();
+                    let onclick = Callback::from(move |_: MouseEvent| {
+                        rejected.set(None);
+                        onremove.emit(i);
+                    });
+                    let handlers = reorder.handlers(i);
+                    html! {
+                        <button
+                            class={classes!("cursor-pointer")}
+                            draggable="true"
+                            {onclick}
+                            ondragstart={handlers.ondragstart}
+                            ondragover={handlers.ondragover}
+                            ondrop={handlers.ondrop}
+                            ondragend={handlers.ondragend}
+                        >
+                            <Image src={ImageType::Item(*item_id)} />
+                        </button>
+                    }
+                }).collect::<Html>()}
+                <Picker<ItemId> options={(*options).clone()} {onselect} placeholder="Add item" />
+            </div>
+            {errors.map(|error| html! {
+                <span class={classes!("text-sm", "text-red-400")}>{error}</span>
+            }).collect::<Html>()}
+        </div>
+    }
+}
@@ -0,0 +1,75 @@
+use crate::data::champions::mechanic;
+use tutorlolv2_gen::ChampionId;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct MechanicInputProps {
+    pub champion_id: ChampionId,
+    pub stacks: u32,
+    pub is_mega_gnar: bool,
+    pub onstacks: Callback<u32>,
+    pub onform: Callback<bool>,
+}
+
+/// Displays only the champion-specific controls that affect the damage
+/// of the selected champion, labeled according to [`mechanic`]
+#[component]
+pub fn MechanicInput(props: &MechanicInputProps) -> Html {
+    let MechanicInputProps {
+        champion_id,
+        stacks,
+        is_mega_gnar,
+        onstacks,
+        onform,
+    } = props;
+
+    let mechanic = mechanic(*champion_id);
+    if mechanic.is_empty() {
+        return html! {};
+    }
+
+    let stacks_input = mechanic.stacks.map(|stack| {
+        let onstacks = onstacks.clone();
+        let oninput = Callback::from(move |e: InputEvent| {
+            let input = e.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(v) = input.value().parse::<u32>() {
+                onstacks.emit(v.min(stack.max));
+            }
+        });
+        html! {
+            <label class={classes!("flex", "gap-2", "items-center")} title={stack.description}>
+                <span>{stack.label}</span>
+                <input
+                    type="number"
+                    class={classes!("w-16", "bg-std-900")}
+                    min="0"
+                    max={stack.max.to_string()}
+                    step={stack.step.to_string()}
+                    value={stacks.to_string()}
+                    {oninput}
+                />
+            </label>
+        }
+    });
+
+    let form_input = mechanic.form.map(|form| {
+        let onform = onform.clone();
+        let onchange = Callback::from(move |e: Event| {
+            onform.emit(e.target_unchecked_into::<HtmlInputElement>().checked())
+        });
+        html! {
+            <label class={classes!("flex", "gap-2", "items-center")}>
+                <input type="checkbox" checked={*is_mega_gnar} {onchange} />
+                <span>{form}</span>
+            </label>
+        }
+    });
+
+    html! {
+        <div class={classes!("flex", "gap-4")}>
+            {stacks_input}
+            {form_input}
+        </div>
+    }
+}
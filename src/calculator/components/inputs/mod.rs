@@ -1,3 +1,9 @@
+pub mod champion;
 pub mod dragon;
 pub mod enemies;
+pub mod items;
+pub mod mechanic;
 pub mod player;
+pub mod runes;
+pub mod stacks;
+pub mod stats;
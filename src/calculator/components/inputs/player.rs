@@ -1,9 +1,15 @@
-use crate::calculator::{
-    Player,
-    page::PlayerProps,
-    reducer::{LastAction, PlayerAction},
+use crate::{
+    calculator::{
+        Player,
+        components::inputs::{
+            champion::ChampionSelect, items::ItemsInput, mechanic::MechanicInput,
+            runes::RunePageInput, stacks::StacksInput, stats::StatsSummary,
+        },
+        page::PlayerProps,
+        reducer::{DataAction, PlayerAction},
+    },
+    model::{SimpleStats, Stats},
 };
-use std::{cell::RefCell, rc::Rc};
 use yew::prelude::*;
 
 #[hook]
@@ -11,26 +17,68 @@ pub fn use_player_callback<T: 'static>(
     props: &PlayerProps,
     callback: fn(T) -> PlayerAction,
 ) -> Callback<T> {
-    let PlayerProps {
-        player,
-        last_action,
-    } = props.clone();
-    use_callback((), move |v, _| {
-        let value = callback(v);
-        last_action.replace(value.action());
-        player.dispatch(value);
-    })
+    let PlayerProps { player } = props.clone();
+    use_callback((), move |v, _| player.dispatch(callback(v)))
 }
 
 #[derive(PartialEq, Properties)]
 pub struct PlayerInputProps {
     pub player_props: PlayerProps,
+    pub inferred: Option<Stats>,
 }
 
 #[component]
 pub fn PlayerInput(props: &PlayerInputProps) -> Html {
-    let PlayerInputProps { player_props } = props;
+    let PlayerInputProps {
+        player_props,
+        inferred,
+    } = props;
+    let data = &player_props.player.data;
+    let stats = data.effective_stats(*inferred);
+    let set_champion = use_player_callback(player_props, |v| {
+        PlayerAction::Data(DataAction::ChampionId(v))
+    });
+    let set_stacks =
+        use_player_callback(player_props, |v| PlayerAction::Data(DataAction::Stacks(v)));
+    let set_form = use_player_callback(player_props, |v| {
+        PlayerAction::Data(DataAction::IsMegaGnar(v))
+    });
+    let insert_item = use_player_callback(player_props, |v| {
+        PlayerAction::Data(DataAction::InsertItem(v))
+    });
+    let remove_item = use_player_callback(player_props, |v| {
+        PlayerAction::Data(DataAction::RemoveItem(v))
+    });
+    let move_item = use_player_callback(player_props, |(from, to): (usize, usize)| {
+        PlayerAction::Data(DataAction::MoveItem(from, to))
+    });
+
     html! {
-        <div></div>
+        <div>
+            <ChampionSelect value={data.champion_id} onchange={set_champion} />
+            <StatsSummary
+                stats={SimpleStats {
+                    armor: stats.armor,
+                    health: stats.health,
+                    magic_resist: stats.magic_resist,
+                }}
+                inferred={data.infer_stats && inferred.is_some()}
+            />
+            <MechanicInput
+                champion_id={data.champion_id}
+                stacks={data.stacks}
+                is_mega_gnar={data.is_mega_gnar}
+                onstacks={set_stacks}
+                onform={set_form}
+            />
+            <ItemsInput
+                items={data.items.clone()}
+                oninsert={insert_item}
+                onremove={remove_item}
+                onmove={move_item}
+            />
+            <RunePageInput player_props={player_props.clone()} />
+            <StacksInput player_props={player_props.clone()} />
+        </div>
     }
 }
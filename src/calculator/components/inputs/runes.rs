@@ -0,0 +1,123 @@
+use crate::{
+    calculator::{
+        components::inputs::player::use_player_callback, page::PlayerProps, reducer::PlayerAction,
+    },
+    components::image::Image,
+    data::runes::{KEYSTONE_ROW, ROWS, RunePage, RuneTree, retain_trees, select_rune},
+    utils::ImageType,
+};
+use tutorlolv2_gen::RuneId;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct RunePageInputProps {
+    pub player_props: PlayerProps,
+}
+
+#[component]
+pub fn RunePageInput(props: &RunePageInputProps) -> Html {
+    let RunePageInputProps { player_props } = props;
+    let runes = player_props.player.runes.clone();
+    let page = RunePage::new(&runes);
+
+    let set_runes = use_player_callback(player_props, PlayerAction::SetRunes);
+    let chosen_primary = use_state(|| page.primary);
+    let chosen_secondary = use_state(|| page.secondary);
+
+    let primary = page.primary.or(*chosen_primary);
+    let secondary = page
+        .secondary
+        .or(*chosen_secondary)
+        .filter(|tree| Some(*tree) != primary);
+
+    let tree_buttons = |current: Option<RuneTree>, is_primary: bool| {
+        RuneTree::ALL
+            .into_iter()
+            .filter(|tree| is_primary || Some(*tree) != primary)
+            .map(|tree| {
+                let onclick = {
+                    let runes = runes.clone();
+                    let set_runes = set_runes.clone();
+                    let chosen_primary = chosen_primary.clone();
+                    let chosen_secondary = chosen_secondary.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        let (new_primary, new_secondary) = match is_primary {
+                            true => (Some(tree), secondary.filter(|s| *s != tree)),
+                            false => (primary, Some(tree)),
+                        };
+                        chosen_primary.set(new_primary);
+                        chosen_secondary.set(new_secondary);
+                        set_runes.emit(retain_trees(&runes, new_primary, new_secondary));
+                    })
+                };
+                let mut class = classes!("px-2", "py-1", "text-sm");
+                class.push(match Some(tree) == current {
+                    true => "bg-std-700",
+                    false => "bg-std-900",
+                });
+                html! {
+                    <button {class} {onclick}>{tree.name()}</button>
+                }
+            })
+            .collect::<Html>()
+    };
+
+    let rune_row = |tree: RuneTree, row: usize, tree_secondary: Option<RuneTree>| {
+        tree.runes(row)
+            .into_iter()
+            .map(|rune_id: RuneId| {
+                let selected = runes.contains(&rune_id);
+                let onclick = {
+                    let runes = runes.clone();
+                    let set_runes = set_runes.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        set_runes.emit(match selected {
+                            true => runes.iter().copied().filter(|r| *r != rune_id).collect(),
+                            false => select_rune(&runes, rune_id, tree_secondary),
+                        })
+                    })
+                };
+                let mut class = classes!("cursor-pointer");
+                if !selected {
+                    class.push(classes!("opacity-40", "grayscale"));
+                }
+                html! {
+                    <button {class} {onclick}>
+                        <Image src={ImageType::Rune(rune_id)} />
+                    </button>
+                }
+            })
+            .collect::<Html>()
+    };
+
+    let tree_rows = |tree: Option<RuneTree>, first_row: usize| match tree {
+        Some(tree) => (first_row..ROWS)
+            .map(|row| {
+                html! {
+                    <div class={classes!("flex", "gap-1", "min-h-8")}>
+                        {rune_row(tree, row, secondary)}
+                    </div>
+                }
+            })
+            .collect::<Html>(),
+        None => html! {},
+    };
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-2")}>
+            <div class={classes!("flex", "gap-4")}>
+                <div class={classes!("flex", "flex-col", "gap-1")}>
+                    <div class={classes!("flex", "gap-1")}>{tree_buttons(primary, true)}</div>
+                    {tree_rows(primary, KEYSTONE_ROW)}
+                </div>
+                <div class={classes!("flex", "flex-col", "gap-1")}>
+                    <div class={classes!("flex", "gap-1")}>{tree_buttons(secondary, false)}</div>
+                    {tree_rows(secondary, KEYSTONE_ROW + 1)}
+                </div>
+            </div>
+            {page.errors.iter().map(|error| html! {
+                <span class={classes!("text-sm", "text-red-400")}>{error.to_string()}</span>
+            }).collect::<Html>()}
+        </div>
+    }
+}
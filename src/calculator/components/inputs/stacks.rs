@@ -0,0 +1,149 @@
+use crate::{
+    calculator::{
+        components::inputs::player::use_player_callback,
+        page::PlayerProps,
+        reducer::{DataAction, PlayerAction},
+    },
+    components::image::Image,
+    data::{items, runes},
+    model::ValueException,
+    utils::ImageType,
+};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct StackInputProps {
+    pub src: ImageType,
+    pub value: u32,
+    /// Maximum number of stacks of this entity. Values above [`ValueException::VAL_MASK`]
+    /// are bounded to it, since they can not be stored
+    pub max: u32,
+    pub onchange: Callback<u32>,
+}
+
+/// Slider and number input that edit the stacks of a single item or rune
+#[component]
+pub fn StackInput(props: &StackInputProps) -> Html {
+    let StackInputProps {
+        src,
+        value,
+        max,
+        onchange,
+    } = props;
+
+    let bound = (*max).min(ValueException::VAL_MASK);
+    let warning = use_state(|| None::<String>);
+
+    let oninput = {
+        let onchange = onchange.clone();
+        let warning = warning.clone();
+        Callback::from(move |e: InputEvent| {
+            let input = e.target_unchecked_into::<HtmlInputElement>();
+            let Ok(v) = input.value().parse::<u32>() else {
+                return;
+            };
+            if ValueException::would_truncate(v) {
+                warning.set(Some(format!(
+                    "{v} stacks would be truncated to {}",
+                    ValueException::truncate_value(v)
+                )));
+                return;
+            }
+            match v > bound {
+                true => warning.set(Some(format!("At most {bound} stacks can be held"))),
+                false => warning.set(None),
+            }
+            onchange.emit(v.min(bound));
+        })
+    };
+
+    html! {
+        <div class={classes!("flex", "flex-col")}>
+            <div class={classes!("flex", "gap-2", "items-center")}>
+                <Image src={*src} focusable={true} />
+                <input
+                    type="range"
+                    min="0"
+                    max={bound.to_string()}
+                    value={value.to_string()}
+                    oninput={oninput.clone()}
+                />
+                <input
+                    type="number"
+                    class={classes!("w-16", "bg-std-900", "text-sm")}
+                    min="0"
+                    value={value.to_string()}
+                    {oninput}
+                />
+            </div>
+            {match *warning {
+                Some(ref text) => html! {
+                    <span class={classes!("text-sm", "text-yellow-400")}>{text}</span>
+                },
+                None => html! {},
+            }}
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct StacksInputProps {
+    pub player_props: PlayerProps,
+}
+
+/// Lists every stackable item and rune of the current player once, editing the
+/// associated [`ValueException`] in place, or creating it when the entity has no
+/// stacks yet
+#[component]
+pub fn StacksInput(props: &StacksInputProps) -> Html {
+    let StacksInputProps { player_props } = props;
+    let player = &player_props.player;
+    let dispatch = use_player_callback(player_props, core::convert::identity);
+
+    let item_ids = &player.data.items;
+    let item_inputs = item_ids
+        .iter()
+        .enumerate()
+        .filter(|(i, item_id)| !item_ids[..*i].contains(item_id))
+        .filter_map(|(_, &item_id)| Some((item_id, items::max_stacks(item_id)?)))
+        .map(|(item_id, max)| {
+            let value = player
+                .data
+                .item_exceptions
+                .iter()
+                .find(|exc| exc.get_item_id() == Some(item_id))
+                .map_or(0, |exc| exc.stacks());
+            let onchange = dispatch.reform(move |stacks| {
+                PlayerAction::Data(DataAction::InsertItemExc(item_id, stacks))
+            });
+            html! {
+                <StackInput src={ImageType::Item(item_id)} {value} {max} {onchange} />
+            }
+        });
+
+    let rune_ids = &player.runes;
+    let rune_inputs = rune_ids
+        .iter()
+        .enumerate()
+        .filter(|(i, rune_id)| !rune_ids[..*i].contains(rune_id))
+        .filter_map(|(_, &rune_id)| Some((rune_id, runes::max_stacks(rune_id)?)))
+        .map(|(rune_id, max)| {
+            let value = player
+                .rune_exceptions
+                .iter()
+                .find(|exc| exc.get_rune_id() == Some(rune_id))
+                .map_or(0, |exc| exc.stacks());
+            let onchange =
+                dispatch.reform(move |stacks| PlayerAction::InsertRuneExc(rune_id, stacks));
+            html! {
+                <StackInput src={ImageType::Rune(rune_id)} {value} {max} {onchange} />
+            }
+        });
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-1")}>
+            {item_inputs.chain(rune_inputs).collect::<Html>()}
+        </div>
+    }
+}
@@ -0,0 +1,27 @@
+use crate::model::SimpleStats;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct StatsSummaryProps {
+    pub stats: SimpleStats,
+    /// Whether these stats were inferred by the calculator
+    pub inferred: bool,
+}
+
+/// Displays the resistances and health that are used for some player
+#[component]
+pub fn StatsSummary(props: &StatsSummaryProps) -> Html {
+    let StatsSummaryProps { stats, inferred } = props;
+
+    html! {
+        <div class={classes!("flex", "gap-3", "text-sm", "text-std-400")}>
+            <span>{format!("Health {}", stats.health)}</span>
+            <span>{format!("Armor {}", stats.armor)}</span>
+            <span>{format!("Magic Resist {}", stats.magic_resist)}</span>
+            {match inferred {
+                true => html! { <span class={classes!("italic")}>{"inferred"}</span> },
+                false => html! {},
+            }}
+        </div>
+    }
+}
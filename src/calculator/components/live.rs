@@ -0,0 +1,154 @@
+use crate::{
+    calculator::{
+        Player,
+        live::{DEFAULT_ENDPOINT, LiveGame, POLL_INTERVAL, fetch_live_game},
+        reducer::{Allies, DragonAction, Enemies, PlayerAction},
+    },
+    model::{Dragons, Team},
+};
+use gloo_timers::callback::Interval;
+use std::{cell::Cell, rc::Rc};
+use web_sys::{AbortController, HtmlInputElement};
+use yew::{platform::spawn_local, prelude::*};
+
+#[derive(PartialEq, Properties)]
+pub struct LiveGameInputProps {
+    pub player: UseReducerHandle<Player>,
+    pub allies: UseReducerHandle<Allies>,
+    pub enemies: UseReducerHandle<Enemies>,
+    pub dragons: UseReducerHandle<Dragons>,
+    pub team: UseStateHandle<Team>,
+}
+
+/// Polls the live client while enabled, replacing the calculator input with the
+/// data of the running game every [`POLL_INTERVAL`] milliseconds
+#[component]
+pub fn LiveGameInput(props: &LiveGameInputProps) -> Html {
+    let LiveGameInputProps {
+        player,
+        allies,
+        enemies,
+        dragons,
+        team,
+    } = props;
+
+    let endpoint = use_state(|| String::from(DEFAULT_ENDPOINT));
+    let enabled = use_state(|| false);
+    let status = use_state(|| None::<String>);
+
+    // Handles captured by the interval would hold the state of the first render,
+    // so the latest ones are stored here on every render instead
+    let latest = use_mut_ref(|| (player.clone(), allies.clone(), enemies.clone()));
+    *latest.borrow_mut() = (player.clone(), allies.clone(), enemies.clone());
+
+    {
+        let dragons = dragons.clone();
+        let team = team.clone();
+        let status = status.clone();
+        use_effect_with(
+            ((*endpoint).clone(), *enabled),
+            move |(endpoint, enabled)| {
+                // Aborted when polling stops, so a pending request can not load
+                // its data afterwards. Ticks are skipped while a request is pending,
+                // so responses can not arrive out of order
+                let controller = AbortController::new().ok();
+                let pending = Rc::new(Cell::new(false));
+                let poll = {
+                    let endpoint = endpoint.clone();
+                    let signal = controller.as_ref().map(|c| c.signal());
+                    move || {
+                        if pending.replace(true) {
+                            return;
+                        }
+                        let endpoint = endpoint.clone();
+                        let signal = signal.clone();
+                        let pending = pending.clone();
+                        let latest = latest.clone();
+                        let dragons = dragons.clone();
+                        let team = team.clone();
+                        let status = status.clone();
+                        spawn_local(async move {
+                            let result = fetch_live_game(&endpoint, signal.as_ref())
+                                .await
+                                .and_then(|data| LiveGame::new(&data, &latest.borrow().0));
+                            pending.set(false);
+                            if signal.as_ref().is_some_and(|signal| signal.aborted()) {
+                                return;
+                            }
+                            match result {
+                                Ok(game) => {
+                                    let (player, allies, enemies) = latest.borrow().clone();
+                                    team.set(game.team);
+                                    dragons.dispatch(DragonAction::Load(game.dragons));
+                                    // Players are updated in place, so they keep
+                                    // their ids between polls
+                                    for action in allies.sync(game.allies) {
+                                        allies.dispatch(action);
+                                    }
+                                    for action in enemies.sync(game.enemies) {
+                                        enemies.dispatch(action);
+                                    }
+                                    player.dispatch(PlayerAction::Load(Box::new(game.player)));
+                                    status.set(None);
+                                }
+                                Err(e) => status.set(Some(e.to_string())),
+                            }
+                        });
+                    }
+                };
+
+                let interval = enabled.then(|| {
+                    poll();
+                    Interval::new(POLL_INTERVAL, poll)
+                });
+                move || {
+                    drop(interval);
+                    if let Some(controller) = controller {
+                        controller.abort();
+                    }
+                }
+            },
+        );
+    }
+
+    let on_endpoint = {
+        let endpoint = endpoint.clone();
+        Callback::from(move |e: Event| {
+            endpoint.set(e.target_unchecked_into::<HtmlInputElement>().value())
+        })
+    };
+
+    let on_toggle = {
+        let enabled = enabled.clone();
+        let status = status.clone();
+        Callback::from(move |_: MouseEvent| {
+            status.set(None);
+            enabled.set(!*enabled)
+        })
+    };
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-1")}>
+            <div class={classes!("flex", "gap-2", "items-center")}>
+                <input
+                    type="text"
+                    class={classes!("bg-std-900", "w-96", "text-sm")}
+                    value={(*endpoint).clone()}
+                    onchange={on_endpoint}
+                />
+                <button class={classes!("px-2", "py-1", "bg-std-800")} onclick={on_toggle}>
+                    {match *enabled {
+                        true => "Stop live game",
+                        false => "Live game",
+                    }}
+                </button>
+            </div>
+            {match *status {
+                Some(ref error) => html! {
+                    <span class={classes!("text-sm", "text-red-400")}>{error}</span>
+                },
+                None => html! {},
+            }}
+        </div>
+    }
+}
@@ -1 +1,7 @@
+pub mod aoe;
+pub mod export;
 pub mod inputs;
+pub mod live;
+pub mod palette;
+pub mod sweep;
+pub mod teams;
@@ -0,0 +1,301 @@
+use crate::{
+    calculator::{
+        Player, PlayerData,
+        commands::{Command, DragonKind, Suggestion, suggestions},
+        reducer::{DataAction, DragonAction, Enemies, EnemyAction, PlayerAction},
+    },
+    data::{
+        items::validate_insert,
+        runes::{RunePage, select_rune},
+    },
+    model::Dragons,
+    worker::{
+        WorkerRequest, WorkerResponse,
+        client::{cancel_request, send_request},
+    },
+};
+use std::rc::Rc;
+use wasm_bindgen::{JsCast, closure::Closure};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct CommandPaletteProps {
+    pub player: UseReducerHandle<Player>,
+    pub enemies: UseReducerHandle<Enemies>,
+    pub dragons: UseReducerHandle<Dragons>,
+}
+
+/// Dispatches the actions of `command`, returning an error message if it
+/// can not be applied to the current input
+fn run(command: Command, props: &CommandPaletteProps) -> Result<(), String> {
+    let CommandPaletteProps {
+        player,
+        enemies,
+        dragons,
+    } = props;
+
+    let dispatch_player = |action: PlayerAction| player.dispatch(action);
+    let data = &player.data;
+
+    match command {
+        Command::SetChampion(v) => dispatch_player(PlayerAction::Data(DataAction::ChampionId(v))),
+        Command::AddItem(v) => {
+            validate_insert(&data.items, v).map_err(|e| e.to_string())?;
+            dispatch_player(PlayerAction::Data(DataAction::InsertItem(v)));
+        }
+        Command::RemoveItem(v) => {
+            let index = data
+                .items
+                .iter()
+                .position(|item_id| *item_id == v)
+                .ok_or_else(|| format!("{} is not in the build", v.name()))?;
+            dispatch_player(PlayerAction::Data(DataAction::RemoveItem(index)));
+        }
+        Command::AddRune(v) => {
+            let secondary = RunePage::new(&player.runes).secondary;
+            dispatch_player(PlayerAction::SetRunes(select_rune(
+                &player.runes,
+                v,
+                secondary,
+            )));
+        }
+        Command::RemoveRune(v) => {
+            let index = player
+                .runes
+                .iter()
+                .position(|rune_id| *rune_id == v)
+                .ok_or_else(|| format!("{} is not in the rune page", v.name()))?;
+            dispatch_player(PlayerAction::RemoveRune(index));
+        }
+        Command::SetLevel(v) => dispatch_player(PlayerAction::Data(DataAction::Level(v))),
+        Command::SetAbility(slot, v) => {
+            let mut abilities = player.abilities;
+            abilities.set(slot, v);
+            dispatch_player(PlayerAction::AbilityLevel(abilities));
+        }
+        Command::SetStacks(v) => dispatch_player(PlayerAction::Data(DataAction::Stacks(v))),
+        Command::ToggleInferStats => dispatch_player(PlayerAction::Data(DataAction::InferStats(
+            !data.infer_stats,
+        ))),
+        Command::ToggleMegaGnar => dispatch_player(PlayerAction::Data(DataAction::IsMegaGnar(
+            !data.is_mega_gnar,
+        ))),
+        Command::AddEnemy(v) => {
+            enemies.dispatch(EnemyAction::Insert(PlayerData {
+                champion_id: v,
+                ..Default::default()
+            }));
+        }
+        Command::RemoveEnemy(v) => {
+            let id = enemies
+                .iter()
+                .position(|enemy| enemy.champion_id == v)
+                .and_then(|index| enemies.id(index))
+                .ok_or_else(|| format!("{} is not an enemy", v.name()))?;
+            enemies.dispatch(EnemyAction::Remove(id));
+        }
+        Command::SetDragons(kind, v) => {
+            dragons.dispatch(match kind {
+                DragonKind::AllyFire => DragonAction::AllyFire(v),
+                DragonKind::AllyEarth => DragonAction::AllyEarth(v),
+                DragonKind::AllyChemtech => DragonAction::AllyChemtech(v),
+                DragonKind::EnemyEarth => DragonAction::EnemyEarth(v),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Text input opened with `Ctrl+K` that runs [`Command`]s, so that the whole
+/// calculator input can be edited from the keyboard
+#[component]
+pub fn CommandPalette(props: &CommandPaletteProps) -> Html {
+    let open = use_state(|| false);
+    let query = use_state(String::new);
+    let highlighted = use_state(|| 0usize);
+    let error = use_state(|| None::<String>);
+    let entries = use_state(|| Rc::new(suggestions("")));
+    let input = use_node_ref();
+
+    {
+        let entries = entries.clone();
+        use_effect_with((*query).clone(), move |query| {
+            // Matching every champion, item and rune runs in the worker, and the
+            // previous entries stay listed until the results of `query` arrive
+            let request = match query.trim().is_empty() {
+                true => None,
+                false => {
+                    let entries = entries.clone();
+                    let on_response = Callback::from(move |response| {
+                        if let WorkerResponse::Suggestions(list) = response {
+                            entries.set(Rc::new(list));
+                        }
+                    });
+                    send_request(WorkerRequest::Suggest(query.clone()), on_response)
+                }
+            };
+            if request.is_none() {
+                entries.set(Rc::new(suggestions(query)));
+            }
+            move || {
+                if let Some(id) = request {
+                    cancel_request(id);
+                }
+            }
+        });
+    }
+
+    {
+        let open = open.clone();
+        use_effect_with((), move |_| {
+            let listener = Closure::<dyn Fn(KeyboardEvent)>::new(move |e: KeyboardEvent| {
+                if (e.ctrl_key() || e.meta_key()) && e.key().eq_ignore_ascii_case("k") {
+                    e.prevent_default();
+                    open.set(true);
+                }
+            });
+            let window = web_sys::window();
+            if let Some(window) = &window {
+                let _ = window
+                    .add_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref());
+            }
+            move || {
+                if let Some(window) = &window {
+                    let _ = window.remove_event_listener_with_callback(
+                        "keydown",
+                        listener.as_ref().unchecked_ref(),
+                    );
+                }
+            }
+        });
+    }
+
+    {
+        let input = input.clone();
+        use_effect_with(*open, move |open| {
+            if *open && let Some(element) = input.cast::<HtmlInputElement>() {
+                let _ = element.focus();
+            }
+        });
+    }
+
+    if !*open {
+        return html! {};
+    }
+
+    let entries = (*entries).clone();
+
+    let close = {
+        let open = open.clone();
+        let query = query.clone();
+        let error = error.clone();
+        Callback::from(move |_: ()| {
+            open.set(false);
+            query.set(String::new());
+            error.set(None);
+        })
+    };
+
+    let execute = {
+        let close = close.clone();
+        let error = error.clone();
+        let player = props.player.clone();
+        let enemies = props.enemies.clone();
+        let dragons = props.dragons.clone();
+        Callback::from(move |suggestion: Suggestion| {
+            let Some(command) = suggestion.command else {
+                return;
+            };
+            let props = CommandPaletteProps {
+                player: player.clone(),
+                enemies: enemies.clone(),
+                dragons: dragons.clone(),
+            };
+            match run(command, &props) {
+                Ok(()) => close.emit(()),
+                Err(e) => error.set(Some(e)),
+            }
+        })
+    };
+
+    let on_input = {
+        let query = query.clone();
+        let highlighted = highlighted.clone();
+        let error = error.clone();
+        Callback::from(move |e: InputEvent| {
+            highlighted.set(0);
+            error.set(None);
+            query.set(e.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let on_keydown = {
+        let entries = entries.clone();
+        let highlighted = highlighted.clone();
+        let execute = execute.clone();
+        let close = close.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            let last = entries.len().saturating_sub(1);
+            match e.key().as_str() {
+                "ArrowDown" => highlighted.set((*highlighted + 1).min(last)),
+                "ArrowUp" => highlighted.set(highlighted.saturating_sub(1)),
+                "Enter" => {
+                    if let Some(suggestion) = entries.get(*highlighted) {
+                        execute.emit(suggestion.clone());
+                    }
+                }
+                "Escape" => close.emit(()),
+                _ => return,
+            }
+            e.prevent_default();
+        })
+    };
+
+    let list = entries
+        .iter()
+        .enumerate()
+        .map(|(i, suggestion)| {
+            let onmousedown = {
+                let execute = execute.clone();
+                let suggestion = suggestion.clone();
+                Callback::from(move |e: MouseEvent| {
+                    e.prevent_default();
+                    execute.emit(suggestion.clone());
+                })
+            };
+            let mut class = classes!("px-2", "py-1", "cursor-pointer");
+            if i == *highlighted {
+                class.push("bg-std-700");
+            }
+            if suggestion.command.is_none() {
+                class.push("text-std-400");
+            }
+            html! { <li {class} {onmousedown}>{&suggestion.label}</li> }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class={classes!("fixed", "inset-0", "z-40", "flex", "justify-center", "pt-32", "bg-black/50")}>
+            <div class={classes!("flex", "flex-col", "w-[32rem]", "h-fit", "bg-std-900", "border", "border-std-700")}>
+                <input
+                    ref={input}
+                    type="text"
+                    class={classes!("bg-std-800", "px-2", "py-1")}
+                    placeholder="Type a command, such as \"add item rabadon\" or \"set q 5\""
+                    value={(*query).clone()}
+                    oninput={on_input}
+                    onkeydown={on_keydown}
+                    onblur={close.reform(|_: FocusEvent| ())}
+                />
+                <ul>{list}</ul>
+                {match *error {
+                    Some(ref error) => html! {
+                        <span class={classes!("px-2", "text-sm", "text-red-400")}>{error}</span>
+                    },
+                    None => html! {},
+                }}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{
+    calculator::{
+        InputGame,
+        sweep::{SweepAxis, SweepResult},
+    },
+    components::task::use_worker_task,
+    utils::download::{download_svg_as_png, download_text},
+    worker::{WorkerRequest, WorkerResponse},
+};
+use std::rc::Rc;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct SweepPanelProps {
+    pub input_game: InputGame,
+}
+
+#[component]
+pub fn SweepPanel(props: &SweepPanelProps) -> Html {
+    let SweepPanelProps { input_game } = props;
+
+    let axis = use_state(|| SweepAxis::Level);
+    let enemy_index = use_state(|| 0usize);
+    let result = use_state(|| None::<Rc<SweepResult>>);
+    let task = {
+        let result = result.clone();
+        use_worker_task(Callback::from(move |response| {
+            if let WorkerResponse::Sweep(data) = response {
+                result.set(Some(Rc::new(data)));
+            }
+        }))
+    };
+
+    let on_axis = {
+        let axis = axis.clone();
+        Callback::from(move |e: Event| {
+            let index = e
+                .target_unchecked_into::<HtmlSelectElement>()
+                .selected_index();
+            if let Some(&value) = SweepAxis::ALL.get(index as usize) {
+                axis.set(value);
+            }
+        })
+    };
+
+    let on_enemy = {
+        let enemy_index = enemy_index.clone();
+        Callback::from(move |e: Event| {
+            let index = e
+                .target_unchecked_into::<HtmlSelectElement>()
+                .selected_index();
+            enemy_index.set(index.max(0) as usize);
+        })
+    };
+
+    let on_run = {
+        let input_game = input_game.clone();
+        let axis = *axis;
+        let enemy_index = *enemy_index;
+        let task = task.clone();
+        Callback::from(move |_: MouseEvent| {
+            task.start(WorkerRequest::RunSweep {
+                input: input_game.clone(),
+                axis,
+                enemy_index,
+            })
+        })
+    };
+
+    let export = |kind: &'static str| {
+        let result = result.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(result) = &*result else {
+                return;
+            };
+            let exported = match kind {
+                "csv" => download_text(&result.file_name(kind), "text/csv", &result.to_csv()),
+                "svg" => download_text(&result.file_name(kind), "image/svg+xml", &result.to_svg()),
+                _ => download_svg_as_png(
+                    &result.file_name(kind),
+                    &result.to_svg(),
+                    SweepResult::CHART_WIDTH,
+                    SweepResult::CHART_HEIGHT,
+                ),
+            };
+            if let Err(e) = exported {
+                web_sys::console::error_1(&format!("Failed to export sweep: {e:?}").into());
+            }
+        })
+    };
+
+    let button = classes!("px-2", "py-1", "bg-std-800", "hover:bg-std-700");
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-2")}>
+            <div class={classes!("flex", "gap-2", "items-center")}>
+                <select class={classes!("bg-std-900")} onchange={on_axis}>
+                    {SweepAxis::ALL.iter().map(|value| html! {
+                        <option selected={*value == *axis}>{value.label()}</option>
+                    }).collect::<Html>()}
+                </select>
+                <select class={classes!("bg-std-900")} onchange={on_enemy}>
+                    {input_game.enemy_players.iter().enumerate().map(|(i, enemy)| html! {
+                        <option selected={i == *enemy_index}>{enemy.champion_id.name()}</option>
+                    }).collect::<Html>()}
+                </select>
+                {match task.is_running() {
+                    true => html! {},
+                    false => html! {
+                        <button
+                            class={button.clone()}
+                            disabled={input_game.enemy_players.is_empty()}
+                            onclick={on_run}
+                        >
+                            {"Run sweep"}
+                        </button>
+                    },
+                }}
+                {task.view()}
+            </div>
+            {match *result {
+                Some(ref data) => html! {
+                    <div class={classes!("flex", "flex-col", "gap-2")}>
+                        {Html::from_html_unchecked(data.to_svg().into())}
+                        <div class={classes!("flex", "gap-2")}>
+                            <button class={button.clone()} onclick={export("csv")}>{"CSV"}</button>
+                            <button class={button.clone()} onclick={export("svg")}>{"SVG"}</button>
+                            <button class={button} onclick={export("png")}>{"PNG"}</button>
+                        </div>
+                    </div>
+                },
+                None => html! {},
+            }}
+        </div>
+    }
+}
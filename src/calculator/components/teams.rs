@@ -0,0 +1,106 @@
+use crate::{
+    calculator::{
+        Player,
+        components::inputs::enemies::EnemiesInput,
+        reducer::{Allies, Enemies, EnemyAction, PlayerAction},
+        teams::Lineup,
+    },
+    components::image::Image,
+    model::Team,
+    utils::ImageType,
+};
+use tutorlolv2_gen::ChampionId;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct TeamsPanelProps {
+    pub player: UseReducerHandle<Player>,
+    pub allies: UseReducerHandle<Allies>,
+    pub enemies: UseReducerHandle<Enemies>,
+    pub team: UseStateHandle<Team>,
+}
+
+/// Displays both teams side by side. Clicking any champion makes it the active
+/// player, and damages are then calculated against the opposite team
+#[component]
+pub fn TeamsPanel(props: &TeamsPanelProps) -> Html {
+    let TeamsPanelProps {
+        player,
+        allies,
+        enemies,
+        team,
+    } = props;
+
+    let entry = |champion_id: ChampionId, level: u8, onclick: Option<Callback<MouseEvent>>| {
+        let mut class = classes!("flex", "items-center", "gap-2", "px-1");
+        match onclick {
+            Some(_) => class.push("cursor-pointer"),
+            None => class.push("bg-std-800"),
+        }
+        html! {
+            <div {class} {onclick}>
+                <Image src={ImageType::Champion(champion_id)} />
+                <span>{champion_id.name()}</span>
+                <span class={classes!("text-sm", "text-std-400")}>{level}</span>
+            </div>
+        }
+    };
+
+    // Players that were active before, whose runes and ability ranks are
+    // restored if they are promoted again
+    let bench = use_mut_ref(Vec::<Player>::new);
+
+    let promote = |target: Team, index: usize| {
+        let bench = bench.clone();
+        let player = player.clone();
+        let allies = allies.clone();
+        let enemies = enemies.clone();
+        let team = team.clone();
+        Callback::from(move |_: MouseEvent| {
+            let lineup = Lineup::promote(
+                &player,
+                &allies,
+                &enemies,
+                *team,
+                target,
+                index,
+                &bench.borrow(),
+            );
+            *bench.borrow_mut() = lineup.bench;
+            allies.dispatch(EnemyAction::Load(lineup.allies));
+            enemies.dispatch(EnemyAction::Load(lineup.enemies));
+            player.dispatch(PlayerAction::Load(Box::new(lineup.player)));
+            team.set(lineup.team);
+        })
+    };
+
+    let column = |side: Team| {
+        let is_active_side = side == **team;
+        let players = match is_active_side {
+            true => &allies,
+            false => &enemies,
+        };
+        html! {
+            <div class={classes!("flex", "flex-col", "gap-1")}>
+                <span>{side.name()}</span>
+                {match is_active_side {
+                    true => entry(player.data.champion_id, player.data.level, None),
+                    false => html! {},
+                }}
+                {players.iter().enumerate().map(|(i, p)| {
+                    entry(p.champion_id, p.level, Some(promote(side, i)))
+                }).collect::<Html>()}
+            </div>
+        }
+    };
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-2")}>
+            <div class={classes!("flex", "gap-8")}>
+                {column(Team::Blue)}
+                {column(Team::Red)}
+            </div>
+            <EnemiesInput enemies={allies.clone()} />
+        </div>
+    }
+}
@@ -0,0 +1,559 @@
+//! Evaluation of cached formulas with the values of the current game. Formulas
+//! are generated source code, so only their arithmetic is evaluated: every known
+//! variable is replaced by its value, and then the innermost operations whose
+//! result does not depend on operator precedence are reduced, one per step.
+//! Anything else, such as function calls, is kept as is
+use crate::calculator::{AbilityLevels, AbilitySlot, Game};
+use std::fmt::Write;
+
+/// Maximum number of reductions of a single line, which prevents long
+/// formulas from producing a wall of steps
+const MAX_STEPS: usize = 24;
+
+/// Value of a variable that may appear in a formula
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Binding {
+    pub name: &'static str,
+    pub value: f64,
+}
+
+/// Values of every known variable, taken from the active player of `game`,
+/// its ability `ranks`, and the enemy at `enemy_index`
+pub fn bindings(game: &Game, ranks: AbilityLevels, enemy_index: usize) -> Vec<Binding> {
+    let player = &game.current_player;
+    let current = player.current_stats;
+    let (base, bonus) = (player.base_stats, player.bonus_stats);
+    let rank = |slot| ranks.get(slot) as i32;
+
+    let mut values = vec![
+        ("level", player.level as i32),
+        ("q_level", rank(AbilitySlot::Q)),
+        ("w_level", rank(AbilitySlot::W)),
+        ("e_level", rank(AbilitySlot::E)),
+        ("r_level", rank(AbilitySlot::R)),
+        ("ap", current.ability_power),
+        ("ad", current.attack_damage),
+        ("armor", current.armor),
+        ("magic_resist", current.magic_resist),
+        ("health", current.health),
+        ("max_health", current.health),
+        ("current_health", current.current_health),
+        ("missing_health", current.health - current.current_health),
+        ("mana", current.mana),
+        ("max_mana", current.mana),
+        ("current_mana", current.current_mana),
+        ("attack_speed", current.attack_speed),
+        ("crit_chance", current.crit_chance),
+        ("crit_damage", current.crit_damage),
+        ("armor_penetration_flat", current.armor_penetration_flat),
+        (
+            "armor_penetration_percent",
+            current.armor_penetration_percent,
+        ),
+        ("magic_penetration_flat", current.magic_penetration_flat),
+        (
+            "magic_penetration_percent",
+            current.magic_penetration_percent,
+        ),
+        ("base_ad", base.attack_damage),
+        ("base_armor", base.armor),
+        ("base_health", base.health),
+        ("base_magic_resist", base.magic_resist),
+        ("base_mana", base.mana),
+        ("bonus_ad", bonus.attack_damage),
+        ("bonus_armor", bonus.armor),
+        ("bonus_health", bonus.health),
+        ("bonus_magic_resist", bonus.magic_resist),
+        ("bonus_mana", bonus.mana),
+    ];
+    if let Some(enemy) = game.enemies.get(enemy_index) {
+        let current = enemy.current_stats;
+        values.extend([
+            ("enemy_level", enemy.level as i32),
+            ("enemy_armor", current.armor),
+            ("enemy_health", current.health),
+            ("enemy_max_health", current.health),
+            ("enemy_magic_resist", current.magic_resist),
+            ("enemy_base_armor", enemy.base_stats.armor),
+            ("enemy_base_health", enemy.base_stats.health),
+            ("enemy_base_magic_resist", enemy.base_stats.magic_resist),
+            ("enemy_bonus_armor", enemy.bonus_stats.armor),
+            ("enemy_bonus_health", enemy.bonus_stats.health),
+            ("enemy_bonus_magic_resist", enemy.bonus_stats.magic_resist),
+            ("enemy_real_armor", enemy.real_armor),
+            ("enemy_real_magic_resist", enemy.real_magic_resist),
+        ]);
+    }
+
+    values
+        .into_iter()
+        .map(|(name, value)| Binding {
+            name,
+            value: value as f64,
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Operator(char),
+    Open,
+    Close,
+    /// Any other text, such as identifiers, keywords and punctuation
+    Other(String),
+}
+
+impl Token {
+    /// Returns true for identifiers and keywords, which are values or calls
+    /// when followed by a minus sign or parentheses
+    fn is_word(&self) -> bool {
+        match self {
+            Token::Other(text) => text.starts_with(|c: char| c.is_alphanumeric() || c == '_'),
+            _ => false,
+        }
+    }
+
+    /// Binding strength of an operator, where higher values bind tighter
+    fn precedence(&self) -> Option<u8> {
+        match self {
+            Token::Operator('+' | '-') => Some(1),
+            Token::Operator('*' | '/') => Some(2),
+            _ => None,
+        }
+    }
+}
+
+/// Formats `value` with at most two decimals, without trailing zeros
+pub fn format_number(value: f64) -> String {
+    let text = format!("{value:.2}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => String::from("0"),
+        _ => text.to_string(),
+    }
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(value) => f.write_str(&format_number(*value)),
+            Token::Operator(op) => write!(f, "{op}"),
+            Token::Open => f.write_str("("),
+            Token::Close => f.write_str(")"),
+            Token::Other(text) => f.write_str(text),
+        }
+    }
+}
+
+/// Returns the text of the HTML produced by the highlighter, without its tags
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    [
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&quot;", "\""),
+        ("&#39;", "'"),
+        ("&amp;", "&"),
+    ]
+    .into_iter()
+    .fold(text, |text, (entity, c)| text.replace(entity, c))
+}
+
+/// Parses a numeric literal, ignoring underscores and type suffixes such as `f32`
+fn parse_number(literal: &str) -> Option<f64> {
+    let digits = literal.split(['f', 'i', 'u']).next()?.replace('_', "");
+    digits.parse().ok()
+}
+
+/// Splits a line into tokens, replacing every known variable by its value.
+/// Paths such as `ctx.ap` are looked up by their last segment, and casts
+/// such as `as f32` are dropped, since every value is a number already
+fn tokenize(line: &str, bindings: &[Binding]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        i += 1;
+        match c {
+            c if c.is_whitespace() => {}
+            '+' | '-' | '*' | '/' => tokens.push(Token::Operator(c)),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            c if c.is_ascii_digit() => {
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric() || "._".contains(chars[i]))
+                {
+                    i += 1;
+                }
+                let literal = chars[start..i].iter().collect::<String>();
+                tokens.push(match parse_number(&literal) {
+                    Some(value) => Token::Number(value),
+                    None => Token::Other(literal),
+                });
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                let path = chars[start..i].iter().collect::<String>();
+                let name = path.rsplit('.').next().unwrap_or(&path);
+                if name == "as" {
+                    // Skips the type of the cast
+                    while i < chars.len() && chars[i].is_whitespace() {
+                        i += 1;
+                    }
+                    while i < chars.len() && chars[i].is_alphanumeric() {
+                        i += 1;
+                    }
+                    continue;
+                }
+                tokens.push(match bindings.iter().find(|binding| binding.name == name) {
+                    Some(binding) => Token::Number(binding.value),
+                    None => Token::Other(path),
+                });
+            }
+            c => tokens.push(Token::Other(c.to_string())),
+        }
+    }
+    merge_signs(tokens)
+}
+
+/// Merges minus signs that are not preceded by a value into the number after them
+fn merge_signs(tokens: Vec<Token>) -> Vec<Token> {
+    let mut result = Vec::<Token>::with_capacity(tokens.len());
+    for token in tokens {
+        if let Token::Number(value) = token
+            && let Some(Token::Operator('-')) = result.last()
+            && !result.iter().rev().nth(1).is_some_and(|token| {
+                matches!(token, Token::Number(_) | Token::Close) || token.is_word()
+            })
+        {
+            result.pop();
+            result.push(Token::Number(-value));
+            continue;
+        }
+        result.push(token);
+    }
+    result
+}
+
+/// Joins `tokens` back into a line. The original spacing is lost, so tokens
+/// are separated by single spaces, except around parentheses and punctuation
+fn join(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    let mut previous = None::<&Token>;
+    for token in tokens {
+        let glued = match (previous, token) {
+            (None, _) | (Some(Token::Open), _) | (_, Token::Close) => true,
+            (Some(previous), Token::Open) => previous.is_word(),
+            (_, Token::Other(text)) if matches!(text.as_str(), "," | ";" | "." | ":" | "?") => true,
+            (Some(Token::Other(text)), _) => matches!(text.as_str(), "." | "!"),
+            _ => false,
+        };
+        if !glued {
+            text.push(' ');
+        }
+        let _ = write!(text, "{token}");
+        previous = Some(token);
+    }
+    text
+}
+
+/// Evaluates `tokens`, which must alternate between numbers and operators
+fn compute(tokens: &[Token]) -> Option<f64> {
+    let mut sums = Vec::new();
+    let mut product = match tokens.first()? {
+        Token::Number(value) => *value,
+        _ => return None,
+    };
+    for pair in tokens[1..].chunks(2) {
+        let [Token::Operator(op), Token::Number(value)] = pair else {
+            return None;
+        };
+        match op {
+            '*' => product *= value,
+            '/' => product /= value,
+            '+' => {
+                sums.push(product);
+                product = *value;
+            }
+            _ => {
+                sums.push(product);
+                product = -value;
+            }
+        }
+    }
+    Some(sums.into_iter().sum::<f64>() + product)
+}
+
+/// Returns true if `tokens[start..end]` alternates between numbers and operators
+fn is_arithmetic(tokens: &[Token], start: usize, end: usize) -> bool {
+    end > start + 2
+        && (end - start) % 2 == 1
+        && tokens[start..end]
+            .iter()
+            .enumerate()
+            .all(|(i, token)| match i % 2 {
+                0 => matches!(token, Token::Number(_)),
+                _ => token.precedence().is_some(),
+            })
+}
+
+/// Checks that the operators around `tokens[start..end]` do not bind tighter
+/// than the ones inside it, so that it can be reduced without changing the result
+fn is_independent(tokens: &[Token], start: usize, end: usize) -> bool {
+    let inner = (start..end)
+        .filter_map(|i| tokens[i].precedence())
+        .min()
+        .unwrap_or(u8::MAX);
+    let left = start
+        .checked_sub(1)
+        .and_then(|i| tokens[i].precedence())
+        .is_none_or(|left| left < inner);
+    let right = tokens
+        .get(end)
+        .and_then(|token| token.precedence())
+        .is_none_or(|right| right <= inner);
+    left && right
+}
+
+/// Applies a single reduction to `tokens`, returning false if none was possible
+fn reduce(tokens: &mut Vec<Token>) -> bool {
+    // Parentheses that only hold a number are dropped, unless they are the
+    // arguments of a function call
+    for i in 0..tokens.len().saturating_sub(2) {
+        if let [Token::Open, Token::Number(_), Token::Close] = tokens[i..i + 3]
+            && !i.checked_sub(1).is_some_and(|j| tokens[j].is_word())
+        {
+            tokens.remove(i + 2);
+            tokens.remove(i);
+            return true;
+        }
+    }
+
+    // Candidates are the runs of products inside of maximal runs of arithmetic,
+    // followed by the maximal runs themselves, so that each step is small
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let mut end = i;
+        while end < tokens.len()
+            && match (end - i) % 2 {
+                0 => matches!(tokens[end], Token::Number(_)),
+                _ => tokens[end].precedence().is_some(),
+            }
+        {
+            end += 1;
+        }
+        if (end - i) % 2 == 0 && end > i {
+            end -= 1;
+        }
+        if is_arithmetic(tokens, i, end) {
+            runs.push((i, end));
+        }
+        i = end.max(i + 1);
+    }
+    let products = runs.iter().flat_map(|&(start, end)| {
+        let mut products = Vec::new();
+        let mut first = start;
+        for j in (start + 1..end).step_by(2) {
+            if tokens[j].precedence() == Some(1) {
+                products.push((first, j));
+                first = j + 1;
+            }
+        }
+        products.push((first, end));
+        products
+    });
+    let candidates = products.chain(runs.iter().copied()).collect::<Vec<_>>();
+
+    for (start, end) in candidates {
+        if is_arithmetic(tokens, start, end)
+            && is_independent(tokens, start, end)
+            && let Some(value) = compute(&tokens[start..end])
+        {
+            tokens.splice(start..end, [Token::Number(value)]);
+            return true;
+        }
+    }
+    false
+}
+
+/// A line of a formula, with its variables substituted and every reduction
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvaluatedLine {
+    pub original: String,
+    pub steps: Vec<String>,
+}
+
+/// Evaluation of a whole formula, along with the variables that were replaced
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Evaluation {
+    pub lines: Vec<EvaluatedLine>,
+    pub used: Vec<Binding>,
+}
+
+/// Evaluates every line of the formula `html` with `bindings`. Lines without
+/// any variable or arithmetic only hold their original text
+pub fn evaluate(html: &str, bindings: &[Binding]) -> Evaluation {
+    let text = strip_html(html);
+    let mut evaluation = Evaluation::default();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let mut substituted = false;
+        for binding in bindings {
+            let is_used = line
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .any(|word| word == binding.name);
+            substituted |= is_used;
+            if is_used && !evaluation.used.contains(binding) {
+                evaluation.used.push(*binding);
+            }
+        }
+
+        let mut tokens = tokenize(line, bindings);
+        let mut steps = vec![join(&tokens)];
+        while steps.len() <= MAX_STEPS && reduce(&mut tokens) {
+            steps.push(join(&tokens));
+        }
+        if steps.len() == 1 && !substituted {
+            steps.clear();
+        }
+        evaluation.lines.push(EvaluatedLine {
+            original: line.trim().to_string(),
+            steps,
+        });
+    }
+    evaluation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        calculator::FinalEnemy,
+        utils::{cache::get_cache, fetch::CONFIG},
+    };
+    use tutorlolv2_gen::{ABILITY_FORMULAS, CHAMPION_FORMULAS, ITEM_FORMULAS, RUNE_FORMULAS};
+
+    fn binding(name: &'static str, value: f64) -> Binding {
+        Binding { name, value }
+    }
+
+    fn steps(line: &str, bindings: &[Binding]) -> Vec<String> {
+        let mut evaluation = evaluate(line, bindings);
+        evaluation.lines.remove(0).steps
+    }
+
+    /// Value of `T` with every number set to zero and every enum set to its
+    /// first variant, which is what bincode decodes from zeroed bytes
+    fn zeroed<T: bincode::Decode<()>>() -> T {
+        bincode::decode_from_slice(&[0; 4096], CONFIG).unwrap().0
+    }
+
+    #[test]
+    fn merges_signs_that_follow_no_value() {
+        let ap = [binding("ap", 10.0)];
+        assert_eq!(
+            tokenize("-ap + 3", &ap),
+            [
+                Token::Number(-10.0),
+                Token::Operator('+'),
+                Token::Number(3.0)
+            ]
+        );
+        assert_eq!(
+            tokenize("5 - 3", &ap),
+            [Token::Number(5.0), Token::Operator('-'), Token::Number(3.0)]
+        );
+        assert_eq!(
+            tokenize("f(-2)", &ap),
+            [
+                Token::Other(String::from("f")),
+                Token::Open,
+                Token::Number(-2.0),
+                Token::Close
+            ]
+        );
+        assert_eq!(
+            tokenize("x - 2", &ap),
+            [
+                Token::Other(String::from("x")),
+                Token::Operator('-'),
+                Token::Number(2.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn reduces_products_before_sums() {
+        let values = [binding("a", 10.0), binding("b", 2.0), binding("c", 3.0)];
+        assert_eq!(steps("a - b * c", &values), ["10 - 2 * 3", "10 - 6", "4"]);
+    }
+
+    #[test]
+    fn keeps_operations_bound_to_unknown_values() {
+        assert!(steps("x / 2 * 3", &[]).is_empty());
+        let ap = [binding("ap", 10.0)];
+        assert_eq!(steps("ap + x / 2 * 3", &ap), ["10 + x / 2 * 3"]);
+        assert_eq!(steps("ap / 2 * 3", &ap), ["10 / 2 * 3", "15"]);
+    }
+
+    #[test]
+    fn drops_casts_and_parses_suffixed_literals() {
+        let ap = [binding("ap", 10.0)];
+        assert_eq!(
+            steps("(ctx.ap as f32) * 2.5f32", &ap),
+            ["(10) * 2.5", "10 * 2.5", "25"]
+        );
+    }
+
+    #[test]
+    fn keeps_function_calls() {
+        let ap = [binding("ap", 10.0)];
+        assert_eq!(
+            steps("max(ap, 5) + 1 * 2", &ap),
+            ["max(10, 5) + 1 * 2", "max(10, 5) + 2"]
+        );
+        assert_eq!(steps("min(ap)", &ap), ["min(10)"]);
+    }
+
+    #[test]
+    fn substitutes_variables_of_cached_formulas() {
+        let mut game = zeroed::<Game>();
+        game.enemies = vec![zeroed::<FinalEnemy>()].into();
+        let bindings = bindings(&game, AbilityLevels::default(), 0);
+
+        let ranges = CHAMPION_FORMULAS
+            .iter()
+            .chain(ABILITY_FORMULAS.iter().flat_map(|formulas| formulas.iter()))
+            .chain(ITEM_FORMULAS.iter())
+            .chain(RUNE_FORMULAS.iter());
+        let evaluation = ranges
+            .filter_map(|&range| get_cache(range))
+            .map(|html| evaluate(&html, &bindings))
+            .find(|evaluation| !evaluation.used.is_empty())
+            .expect("No cached formula uses any binding");
+
+        // The first step holds numbers in place of the variables of the line
+        let substituted = evaluation.lines.iter().any(|line| {
+            line.steps
+                .first()
+                .is_some_and(|step| *step != join(&tokenize(&line.original, &[])))
+        });
+        assert!(substituted, "{evaluation:#?}");
+    }
+}
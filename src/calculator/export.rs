@@ -0,0 +1,330 @@
+//! Export of the calculation results as CSV, JSON or Markdown. Every format is
+//! built from the same list of [`Section`]s, so they always hold the same values
+use crate::{
+    calculator::{
+        Game, MonsterDamage,
+        columns::{Column, ColumnGroup, DamageBreakdown, columns},
+    },
+    components::range::format_range,
+    model::{Damages, Stats},
+};
+use serde_json::{Value, json};
+use std::fmt::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub const ALL: [Self; 3] = [Self::Csv, Self::Json, Self::Markdown];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Json => "JSON",
+            Self::Markdown => "Markdown",
+        }
+    }
+
+    pub const fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Markdown => "md",
+        }
+    }
+
+    pub const fn mime(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Json => "application/json",
+            Self::Markdown => "text/markdown",
+        }
+    }
+}
+
+/// Value of a single cell. Ranges are written as `{min} – {max}` in text formats,
+/// and as a two element array in JSON
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(i32),
+    Range(i32, i32),
+}
+
+impl Cell {
+    fn range((minimum, maximum): (i32, i32)) -> Self {
+        match minimum == maximum {
+            true => Self::Number(minimum),
+            false => Self::Range(minimum, maximum),
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Number(value) => value.to_string(),
+            Self::Range(minimum, maximum) => format_range(*minimum, *maximum),
+        }
+    }
+
+    fn json(&self) -> Value {
+        match self {
+            Self::Text(text) => Value::from(text.as_str()),
+            Self::Number(value) => Value::from(*value),
+            Self::Range(minimum, maximum) => Value::from(vec![*minimum, *maximum]),
+        }
+    }
+}
+
+/// Titled table of the export, where every row has one cell for each header
+pub struct Section {
+    pub title: &'static str,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+const fn stat_values(stats: &Stats) -> [(&'static str, i32); 16] {
+    [
+        ("Ability Power", stats.ability_power),
+        ("Armor", stats.armor),
+        ("Armor Penetration", stats.armor_penetration_flat),
+        ("Armor Penetration %", stats.armor_penetration_percent),
+        ("Attack Damage", stats.attack_damage),
+        ("Attack Range", stats.attack_range),
+        ("Attack Speed", stats.attack_speed),
+        ("Crit Chance", stats.crit_chance),
+        ("Crit Damage", stats.crit_damage),
+        ("Current Health", stats.current_health),
+        ("Magic Penetration", stats.magic_penetration_flat),
+        ("Magic Penetration %", stats.magic_penetration_percent),
+        ("Magic Resist", stats.magic_resist),
+        ("Health", stats.health),
+        ("Mana", stats.mana),
+        ("Current Mana", stats.current_mana),
+    ]
+}
+
+impl From<&MonsterDamage> for Damages {
+    fn from(value: &MonsterDamage) -> Self {
+        Damages {
+            attacks: value.attacks,
+            abilities: value.abilities.clone(),
+            items: value.items.clone(),
+            runes: Box::default(),
+        }
+    }
+}
+
+/// Splits the results of `game` into the sections of the export: the stats of
+/// the current player, its damages against each enemy, and its damages against
+/// every jungle monster resistance variation and tower plate count
+pub fn sections(game: &Game) -> Vec<Section> {
+    let player = &game.current_player;
+    let stats = stat_values(&player.current_stats);
+    let columns = columns(
+        &game.abilities_meta,
+        &game.abilities_to_merge,
+        &game.items_meta,
+        &game.runes_meta,
+    );
+    let damage_types = columns
+        .iter()
+        .map(|column| column.damage_type(&game.abilities_meta, &game.items_meta, &game.runes_meta))
+        .collect::<Vec<_>>();
+
+    let player_section = Section {
+        title: "Player",
+        headers: ["Champion", "Level"]
+            .into_iter()
+            .chain(stats.iter().map(|(name, _)| *name))
+            .map(String::from)
+            .collect(),
+        rows: vec![
+            [
+                Cell::Text(player.champion_id.name().to_string()),
+                Cell::Number(player.level as i32),
+            ]
+            .into_iter()
+            .chain(stats.iter().map(|(_, value)| Cell::Number(*value)))
+            .collect(),
+        ],
+    };
+
+    let enemies_section = Section {
+        title: "Enemies",
+        headers: ["Champion", "Level", "Health", "Armor", "Magic Resist"]
+            .into_iter()
+            .map(String::from)
+            .chain(columns.iter().map(Column::label))
+            .chain(
+                DamageBreakdown::default()
+                    .parts()
+                    .iter()
+                    .map(|(_, label, _)| label.to_string()),
+            )
+            .collect(),
+        rows: game
+            .enemies
+            .iter()
+            .map(|enemy| {
+                let mut breakdown = DamageBreakdown::default();
+                let mut row = vec![
+                    Cell::Text(enemy.champion_id.name().to_string()),
+                    Cell::Number(enemy.level as i32),
+                    Cell::Number(enemy.current_stats.health),
+                    Cell::Number(enemy.real_armor),
+                    Cell::Number(enemy.real_magic_resist),
+                ];
+                for (column, damage_type) in columns.iter().zip(damage_types.iter()) {
+                    let range = column.range(&enemy.damages);
+                    breakdown.add(*damage_type, range.1);
+                    row.push(Cell::range(range));
+                }
+                row.extend(
+                    breakdown
+                        .parts()
+                        .iter()
+                        .map(|(_, _, value)| Cell::Number(*value)),
+                );
+                row
+            })
+            .collect(),
+    };
+
+    // Monsters take no damage from runes
+    let monster_columns = columns
+        .iter()
+        .filter(|column| column.group() != ColumnGroup::Runes)
+        .collect::<Vec<_>>();
+    let monsters_section = Section {
+        title: "Monsters",
+        headers: std::iter::once(String::from("Monster"))
+            .chain(monster_columns.iter().map(|column| column.label()))
+            .collect(),
+        rows: game
+            .monster_damages
+            .iter()
+            .enumerate()
+            .map(|(i, monster)| {
+                let damages = Damages::from(monster);
+                std::iter::once(Cell::Text(format!("Monster {}", i + 1)))
+                    .chain(
+                        monster_columns
+                            .iter()
+                            .map(|column| Cell::range(column.range(&damages))),
+                    )
+                    .collect()
+            })
+            .collect(),
+    };
+
+    let towers_section = Section {
+        title: "Towers",
+        headers: vec![String::from("Plates"), String::from("Damage")],
+        rows: game
+            .tower_damages
+            .iter()
+            .enumerate()
+            .map(|(plates, damage)| vec![Cell::Number(plates as i32), Cell::Number(*damage)])
+            .collect(),
+    };
+
+    vec![
+        player_section,
+        enemies_section,
+        monsters_section,
+        towers_section,
+    ]
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+/// Writes each section as a CSV table, separated by an empty line and
+/// preceded by a line with its title, which spreadsheets paste as is
+pub fn to_csv(sections: &[Section]) -> String {
+    let mut csv = String::new();
+    for section in sections {
+        let _ = writeln!(csv, "{}", section.title);
+        let headers = section.headers.iter().map(|header| csv_field(header));
+        let _ = writeln!(csv, "{}", headers.collect::<Vec<_>>().join(","));
+        for row in &section.rows {
+            let cells = row.iter().map(|cell| csv_field(&cell.text()));
+            let _ = writeln!(csv, "{}", cells.collect::<Vec<_>>().join(","));
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Writes an array with one object for each section, holding its title and its
+/// rows. Rows are arrays of `{label, value}` pairs rather than objects, so that
+/// columns keep the order of the other formats, and columns that share a label,
+/// such as abilities with the same name, are all kept
+pub fn to_json(sections: &[Section]) -> String {
+    let sections = sections
+        .iter()
+        .map(|section| {
+            let rows = section
+                .rows
+                .iter()
+                .map(|row| {
+                    section
+                        .headers
+                        .iter()
+                        .zip(row.iter())
+                        .map(|(header, cell)| json!({ "label": header, "value": cell.json() }))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            json!({ "title": section.title, "rows": rows })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string_pretty(&sections).unwrap_or_default()
+}
+
+/// Writes each section as a Markdown table under a heading with its title
+pub fn to_markdown(sections: &[Section]) -> String {
+    let escape = |text: &str| text.replace('|', "\\|");
+    let mut markdown = String::new();
+    for section in sections {
+        let _ = writeln!(markdown, "### {}\n", section.title);
+        let headers = section.headers.iter().map(|header| escape(header));
+        let _ = writeln!(markdown, "| {} |", headers.collect::<Vec<_>>().join(" | "));
+        let _ = writeln!(markdown, "|{}", " --- |".repeat(section.headers.len()));
+        for row in &section.rows {
+            let cells = row.iter().map(|cell| escape(&cell.text()));
+            let _ = writeln!(markdown, "| {} |", cells.collect::<Vec<_>>().join(" | "));
+        }
+        markdown.push('\n');
+    }
+    markdown
+}
+
+/// Exports the results of `game` in the given format
+pub fn export(game: &Game, format: ExportFormat) -> String {
+    let sections = sections(game);
+    match format {
+        ExportFormat::Csv => to_csv(&sections),
+        ExportFormat::Json => to_json(&sections),
+        ExportFormat::Markdown => to_markdown(&sections),
+    }
+}
+
+/// Name of the downloaded file, such as `Ahri_results.csv`
+pub fn file_name(game: &Game, format: ExportFormat) -> String {
+    format!(
+        "{:?}_results.{}",
+        game.current_player.champion_id,
+        format.extension()
+    )
+}
@@ -0,0 +1,56 @@
+//! Stats inferred by the calculator for the players with `infer_stats` enabled.
+//! They are derived from the latest response and kept apart from the input, so
+//! the stats entered by the user are never overwritten, and receiving a response
+//! does not change the input, which would send the same request again
+use crate::{
+    calculator::{
+        Game, Player, PlayerData,
+        reducer::{Enemies, EnemyId},
+    },
+    model::{SimpleStats, Stats},
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InferredStats {
+    pub player: Option<Stats>,
+    enemies: Vec<(EnemyId, SimpleStats)>,
+}
+
+impl InferredStats {
+    /// Reads the inferred stats from `game`, which must be the response to a
+    /// request made with `player` and `enemies`. Enemies are matched by their
+    /// [`EnemyId`], so the result still applies after the list is edited
+    pub fn new(game: &Game, player: &Player, enemies: &Enemies) -> Self {
+        Self {
+            player: player
+                .data
+                .infer_stats
+                .then_some(game.current_player.current_stats),
+            enemies: enemies
+                .ids()
+                .iter()
+                .zip(enemies.iter())
+                .zip(game.enemies.iter())
+                .filter(|((_, enemy), _)| enemy.infer_stats)
+                .map(|((id, _), result)| (*id, result.current_stats))
+                .collect(),
+        }
+    }
+
+    pub fn enemy(&self, id: EnemyId) -> Option<SimpleStats> {
+        self.enemies
+            .iter()
+            .find_map(|(enemy_id, stats)| (*enemy_id == id).then_some(*stats))
+    }
+}
+
+impl<T: Copy> PlayerData<T> {
+    /// Stats used for this player: the inferred ones while `infer_stats` is
+    /// enabled and a response is available, or the ones entered by the user
+    pub fn effective_stats(&self, inferred: Option<T>) -> T {
+        match self.infer_stats {
+            true => inferred.unwrap_or(self.stats),
+            false => self.stats,
+        }
+    }
+}
@@ -0,0 +1,447 @@
+//! Mapping of Riot's Live Client Data API into the calculator input. The game
+//! client serves it at [`DEFAULT_ENDPOINT`] while a match is running, using a
+//! self-signed certificate, so a local proxy or mock server may be used instead
+use crate::{
+    calculator::{AbilityLevels, Player, PlayerData},
+    data::{champions, items, runes},
+    model::{Dragons, SimpleStats, Stats, Team},
+};
+use serde::Deserialize;
+use std::error::Error;
+use web_sys::AbortSignal;
+
+pub const DEFAULT_ENDPOINT: &str = "https://127.0.0.1:2999/liveclientdata/allgamedata";
+
+/// Interval between each request to the live client, in milliseconds
+pub const POLL_INTERVAL: u32 = 5000;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllGameData {
+    pub active_player: ActivePlayer,
+    pub all_players: Vec<LivePlayer>,
+    #[serde(default)]
+    pub events: LiveEvents,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivePlayer {
+    #[serde(default)]
+    pub abilities: LiveAbilities,
+    pub champion_stats: LiveStats,
+    #[serde(default)]
+    pub full_runes: LiveRunes,
+    pub level: u8,
+    #[serde(default)]
+    pub riot_id: String,
+    #[serde(default)]
+    pub summoner_name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LiveAbilities {
+    pub q: Option<LiveAbility>,
+    pub w: Option<LiveAbility>,
+    pub e: Option<LiveAbility>,
+    pub r: Option<LiveAbility>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveAbility {
+    #[serde(default)]
+    pub ability_level: u8,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveRunes {
+    pub keystone: Option<LiveRune>,
+    #[serde(default)]
+    pub general_runes: Vec<LiveRune>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LiveRune {
+    pub id: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveStats {
+    pub ability_power: f64,
+    pub armor: f64,
+    pub armor_penetration_flat: f64,
+    pub armor_penetration_percent: f64,
+    pub attack_damage: f64,
+    pub attack_range: f64,
+    pub attack_speed: f64,
+    pub crit_chance: f64,
+    pub crit_damage: f64,
+    pub current_health: f64,
+    pub magic_penetration_flat: f64,
+    pub magic_penetration_percent: f64,
+    pub magic_resist: f64,
+    pub max_health: f64,
+    pub resource_max: f64,
+    pub resource_value: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LivePlayer {
+    pub champion_name: String,
+    #[serde(default)]
+    pub raw_champion_name: String,
+    #[serde(default)]
+    pub items: Vec<LiveItem>,
+    pub level: u8,
+    #[serde(default)]
+    pub riot_id: String,
+    #[serde(default)]
+    pub summoner_name: String,
+    pub team: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveItem {
+    #[serde(rename = "itemID")]
+    pub item_id: u32,
+    #[serde(default)]
+    pub slot: u8,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LiveEvents {
+    #[serde(rename = "Events", default)]
+    pub events: Vec<LiveEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LiveEvent {
+    #[serde(rename = "EventName")]
+    pub event_name: String,
+    #[serde(rename = "DragonType", default)]
+    pub dragon_type: String,
+    #[serde(rename = "KillerName", default)]
+    pub killer_name: String,
+}
+
+impl From<&LiveStats> for Stats {
+    fn from(value: &LiveStats) -> Self {
+        let round = |v: f64| v.round() as i32;
+        let percent = |v: f64| (v * 100.0).round() as i32;
+        // The live client reports percent penetration as the multiplier applied
+        // to the resistance of the target, so 1.0 means no penetration
+        let penetration = |v: f64| ((1.0 - v) * 100.0).round() as i32;
+        Stats {
+            ability_power: round(value.ability_power),
+            armor: round(value.armor),
+            armor_penetration_flat: round(value.armor_penetration_flat),
+            armor_penetration_percent: penetration(value.armor_penetration_percent),
+            attack_damage: round(value.attack_damage),
+            attack_range: round(value.attack_range),
+            attack_speed: percent(value.attack_speed),
+            crit_chance: percent(value.crit_chance),
+            crit_damage: round(value.crit_damage),
+            current_health: round(value.current_health),
+            magic_penetration_flat: round(value.magic_penetration_flat),
+            magic_penetration_percent: penetration(value.magic_penetration_percent),
+            magic_resist: round(value.magic_resist),
+            health: round(value.max_health),
+            mana: round(value.resource_max),
+            current_mana: round(value.resource_value),
+        }
+    }
+}
+
+impl LivePlayer {
+    /// Riot ids are preferred, since summoner names are no longer unique
+    fn is_named(&self, riot_id: &str, summoner_name: &str) -> bool {
+        match riot_id.is_empty() {
+            false => self.riot_id == riot_id,
+            true => self.summoner_name == summoner_name,
+        }
+    }
+
+    fn is_killer(&self, killer_name: &str) -> bool {
+        self.riot_id == killer_name
+            || self.summoner_name == killer_name
+            || self.riot_id.split('#').next() == Some(killer_name)
+    }
+
+    fn team(&self) -> Team {
+        Team::from(self.team.as_str())
+    }
+
+    fn item_ids(&self) -> Vec<tutorlolv2_gen::ItemId> {
+        let mut live_items = self.items.iter().collect::<Vec<_>>();
+        live_items.sort_by_key(|item| item.slot);
+        live_items
+            .into_iter()
+            .filter_map(|item| items::from_riot_id(item.item_id))
+            .collect()
+    }
+
+    /// `rawChampionName` holds the champion identifier after the last underscore,
+    /// which is tried first since display names may be localized
+    fn champion_id(&self) -> Option<tutorlolv2_gen::ChampionId> {
+        self.raw_champion_name
+            .rsplit('_')
+            .next()
+            .and_then(champions::from_name)
+            .or_else(|| champions::from_name(&self.champion_name))
+    }
+}
+
+/// Calculator input read from a running game
+pub struct LiveGame {
+    pub player: Player,
+    pub allies: Vec<PlayerData<SimpleStats>>,
+    pub enemies: Vec<PlayerData<SimpleStats>>,
+    pub dragons: Dragons,
+    pub team: Team,
+}
+
+impl LiveGame {
+    /// Maps the live data into the calculator input. Fields of the active player that
+    /// the live client does not provide, such as stacks and exceptions, are kept from
+    /// `player`. The other players only hold the fields read from the game, and are
+    /// merged into the current ones with [`Enemies::sync`]. Their stats are not
+    /// exposed by the live client, so they are always inferred
+    ///
+    /// [`Enemies::sync`]: crate::calculator::reducer::Enemies::sync
+    pub fn new(data: &AllGameData, player: &Player) -> Result<Self, Box<dyn Error>> {
+        let active = &data.active_player;
+        let current = data
+            .all_players
+            .iter()
+            .find(|p| p.is_named(&active.riot_id, &active.summoner_name))
+            .ok_or("Active player was not found in the player list")?;
+        let team = current.team();
+
+        let mut new_player = player.clone();
+        new_player.data.champion_id = current
+            .champion_id()
+            .ok_or_else(|| format!("Unknown champion {}", current.champion_name))?;
+        new_player.data.level = active.level;
+        new_player.data.items = current.item_ids();
+        new_player.data.stats = Stats::from(&active.champion_stats);
+        new_player.data.infer_stats = false;
+        new_player.runes = active
+            .full_runes
+            .keystone
+            .iter()
+            .chain(active.full_runes.general_runes.iter())
+            .filter_map(|rune| runes::from_riot_id(rune.id))
+            .fold(Vec::new(), |mut acc, rune_id| {
+                if !acc.contains(&rune_id) {
+                    acc.push(rune_id);
+                }
+                acc
+            });
+        let level = |ability: &Option<LiveAbility>| ability.as_ref().map_or(0, |a| a.ability_level);
+        new_player.abilities = AbilityLevels {
+            q: level(&active.abilities.q),
+            w: level(&active.abilities.w),
+            e: level(&active.abilities.e),
+            r: level(&active.abilities.r),
+        };
+
+        let other_players = |same_team: bool| {
+            data.all_players
+                .iter()
+                .filter(|p| (p.team() == team) == same_team && !std::ptr::eq(*p, current))
+                .filter_map(|p| {
+                    Some(PlayerData {
+                        champion_id: p.champion_id()?,
+                        level: p.level,
+                        items: p.item_ids(),
+                        infer_stats: true,
+                        ..Default::default()
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        let new_allies = other_players(true);
+        let new_enemies = other_players(false);
+
+        let mut dragons = Dragons::default();
+        for event in data
+            .events
+            .events
+            .iter()
+            .filter(|event| event.event_name == "DragonKill")
+        {
+            let is_ally = data
+                .all_players
+                .iter()
+                .find(|p| p.is_killer(&event.killer_name))
+                .is_some_and(|p| p.team() == team);
+            match (event.dragon_type.as_str(), is_ally) {
+                ("Fire", true) => dragons.ally_fire_dragons += 1,
+                ("Earth", true) => dragons.ally_earth_dragons += 1,
+                ("Chemtech", true) => dragons.ally_chemtech_dragons += 1,
+                ("Earth", false) => dragons.enemy_earth_dragons += 1,
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            player: new_player,
+            allies: new_allies,
+            enemies: new_enemies,
+            dragons,
+            team,
+        })
+    }
+}
+
+/// Requests the live client data from `endpoint`, which is cancelled once `signal` is aborted
+pub async fn fetch_live_game(
+    endpoint: &str,
+    signal: Option<&AbortSignal>,
+) -> Result<AllGameData, Box<dyn Error>> {
+    Ok(gloo_net::http::Request::get(endpoint)
+        .abort_signal(signal)
+        .send()
+        .await?
+        .json::<AllGameData>()
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tutorlolv2_gen::{ChampionId, ItemId, RuneId};
+
+    /// Response of the live client during a match, trimmed to five players
+    const FIXTURE: &str = include_str!("../../tests/fixtures/live_client_data.json");
+
+    fn fixture() -> AllGameData {
+        serde_json::from_str(FIXTURE).expect("fixture should match the live client schema")
+    }
+
+    fn load() -> LiveGame {
+        LiveGame::new(&fixture(), &Player::default()).unwrap()
+    }
+
+    fn item_ids(riot_ids: &[u32]) -> Vec<ItemId> {
+        riot_ids
+            .iter()
+            .filter_map(|id| items::from_riot_id(*id))
+            .collect()
+    }
+
+    fn rune_ids(riot_ids: &[u32]) -> Vec<RuneId> {
+        riot_ids
+            .iter()
+            .filter_map(|id| runes::from_riot_id(*id))
+            .collect()
+    }
+
+    #[test]
+    fn converts_stats() {
+        let stats = Stats::from(&fixture().active_player.champion_stats);
+        assert_eq!(
+            stats,
+            Stats {
+                ability_power: 182,
+                armor: 59,
+                armor_penetration_flat: 0,
+                armor_penetration_percent: 0,
+                attack_damage: 72,
+                attack_range: 550,
+                attack_speed: 75,
+                crit_chance: 0,
+                crit_damage: 175,
+                current_health: 1044,
+                magic_penetration_flat: 18,
+                magic_penetration_percent: 40,
+                magic_resist: 41,
+                health: 1210,
+                mana: 884,
+                current_mana: 513,
+            }
+        );
+    }
+
+    #[test]
+    fn loads_active_player() {
+        let game = load();
+        let player = &game.player;
+        assert_eq!(game.team, Team::Blue);
+        assert_eq!(player.data.champion_id, ChampionId::Ahri);
+        assert_eq!(player.data.level, 11);
+        assert!(!player.data.infer_stats);
+        assert_eq!(
+            player.data.stats,
+            Stats::from(&fixture().active_player.champion_stats)
+        );
+        // Items are ordered by their inventory slot
+        assert_eq!(player.data.items, item_ids(&[3089, 3020]));
+        // The keystone is also listed in the general runes, and is taken once
+        assert_eq!(
+            player.runes,
+            rune_ids(&[8112, 8126, 8138, 8106, 8237, 8233])
+        );
+        assert_eq!(
+            player.abilities,
+            AbilityLevels {
+                q: 3,
+                w: 2,
+                e: 1,
+                r: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn splits_players_by_team() {
+        let game = load();
+        let champions = |players: &[PlayerData<SimpleStats>]| {
+            players.iter().map(|p| p.champion_id).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            champions(&game.allies),
+            [ChampionId::Garen, ChampionId::Ashe]
+        );
+        assert_eq!(
+            champions(&game.enemies),
+            [ChampionId::Zed, ChampionId::Kaisa]
+        );
+        assert!(game.enemies.iter().all(|enemy| enemy.infer_stats));
+        assert_eq!(game.enemies[0].level, 11);
+        assert_eq!(game.enemies[1].items, item_ids(&[3124]));
+    }
+
+    #[test]
+    fn keeps_fields_of_the_active_player() {
+        let mut player = Player::default();
+        player.data.stacks = 5;
+        let game = LiveGame::new(&fixture(), &player).unwrap();
+        assert_eq!(game.player.data.stacks, 5);
+    }
+
+    #[test]
+    fn counts_dragons_by_team() {
+        assert_eq!(
+            load().dragons,
+            Dragons {
+                ally_fire_dragons: 1,
+                enemy_earth_dragons: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn requires_active_player() {
+        let mut data = fixture();
+        data.all_players.retain(|p| p.riot_id != "Fox#EUW");
+        assert!(LiveGame::new(&data, &Player::default()).is_err());
+    }
+}
@@ -5,9 +5,18 @@ use tutorlolv2_gen::{
     AbilityId, AdaptativeType, ChampionId, ItemId, MergeData, RuneId, TypeMetadata,
 };
 
+pub mod aggregate;
+pub mod columns;
+pub mod commands;
 mod components;
+pub mod evaluate;
+pub mod export;
+pub mod inferred;
+pub mod live;
 pub mod page;
 mod reducer;
+pub mod sweep;
+pub mod teams;
 
 /// Exact number of resistence variations for jungle monsters
 pub const L_MSTR: usize = 7;
@@ -15,14 +24,14 @@ pub const L_MSTR: usize = 7;
 /// Number of different plates a tower can have. Each tower can have `0..=5` plates
 pub const L_TWRD: usize = 6;
 
-#[derive(Clone, Debug, Encode, PartialEq)]
+#[derive(Clone, Debug, Decode, Encode, PartialEq)]
 pub struct InputGame {
     pub active_player: Player,
     pub enemy_players: Vec<Rc<PlayerData<SimpleStats>>>,
     pub dragons: Dragons,
 }
 
-#[derive(Clone, Debug, Default, Encode, PartialEq)]
+#[derive(Clone, Debug, Decode, Default, Encode, PartialEq)]
 pub struct Player {
     pub runes: Vec<RuneId>,
     pub rune_exceptions: Vec<ValueException>,
@@ -37,7 +46,7 @@ pub struct Player {
 /// have effect if field `champion_id` is also of type [`ChampionId::Gnar`].
 /// Field `stacks` is useless if the associated champion does not have any special
 /// characteristics that are related to stack-scaling
-#[derive(Clone, Debug, Default, Encode, PartialEq)]
+#[derive(Clone, Debug, Decode, Default, Encode, PartialEq)]
 pub struct PlayerData<T> {
     pub stats: T,
     pub items: Vec<ItemId>,
@@ -91,10 +100,92 @@ pub struct Game {
 }
 
 /// Holds the levels of the abilities of a champion
-#[derive(Clone, Copy, Debug, Default, Encode, PartialEq)]
+#[derive(Clone, Copy, Debug, Decode, Default, Encode, PartialEq)]
 pub struct AbilityLevels {
     pub q: u8,
     pub w: u8,
     pub e: u8,
     pub r: u8,
 }
+
+/// Identifies one of the four ability slots that can be leveled up
+#[derive(Clone, Copy, Debug, Decode, Encode, PartialEq)]
+pub enum AbilitySlot {
+    Q,
+    W,
+    E,
+    R,
+}
+
+impl AbilitySlot {
+    pub const ALL: [Self; 4] = [Self::Q, Self::W, Self::E, Self::R];
+
+    /// Maximum rank an ability in this slot can reach
+    pub const fn max_rank(self) -> u8 {
+        match self {
+            Self::R => 3,
+            _ => 5,
+        }
+    }
+
+    pub const fn as_char(self) -> char {
+        match self {
+            Self::Q => 'Q',
+            Self::W => 'W',
+            Self::E => 'E',
+            Self::R => 'R',
+        }
+    }
+}
+
+impl AbilityLevels {
+    pub const fn get(&self, slot: AbilitySlot) -> u8 {
+        match slot {
+            AbilitySlot::Q => self.q,
+            AbilitySlot::W => self.w,
+            AbilitySlot::E => self.e,
+            AbilitySlot::R => self.r,
+        }
+    }
+
+    pub const fn set(&mut self, slot: AbilitySlot, value: u8) {
+        match slot {
+            AbilitySlot::Q => self.q = value,
+            AbilitySlot::W => self.w = value,
+            AbilitySlot::E => self.e = value,
+            AbilitySlot::R => self.r = value,
+        }
+    }
+
+    /// Returns the ability ranks of a champion at the given level, following the
+    /// most common skill order: one point in each basic ability, then maxing `Q`,
+    /// `W` and `E` in that order, while taking `R` at levels 6, 11 and 16
+    pub const fn at_level(level: u8) -> Self {
+        let r = (level >= 6) as u8 + (level >= 11) as u8 + (level >= 16) as u8;
+        let mut points = level.saturating_sub(r);
+        let mut ranks = [0u8; 3];
+
+        let mut i = 0;
+        while i < 3 && points > 0 {
+            ranks[i] = 1;
+            points -= 1;
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < 3 {
+            let missing = 5 - ranks[i];
+            let add = if points < missing { points } else { missing };
+            ranks[i] += add;
+            points -= add;
+            i += 1;
+        }
+
+        Self {
+            q: ranks[0],
+            w: ranks[1],
+            e: ranks[2],
+            r,
+        }
+    }
+}
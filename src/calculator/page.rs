@@ -1,39 +1,60 @@
 use crate::{
     calculator::{
-        Game, InputGame, Player, PlayerData,
-        components::inputs::player::PlayerInput,
-        reducer::{DataAction, DragonAction, Enemies, EnemyAction, LastAction, PlayerAction},
+        Game, InputGame, Player,
+        components::{
+            aoe::AoePanel,
+            export::ExportPanel,
+            inputs::{enemies::EnemiesInput, player::PlayerInput},
+            live::LiveGameInput,
+            palette::CommandPalette,
+            sweep::SweepPanel,
+            teams::TeamsPanel,
+        },
+        inferred::InferredStats,
+        reducer::{Allies, Enemies, EnemyId},
+    },
+    components::{
+        image::{Image, ImageModeToggle},
+        inspector::{InspectorPanel, PinAction, PinnedFormulas},
+        offline::OfflineToggle,
+        tables::table::DamageTable,
+    },
+    data::items::validate_build,
+    model::{Dragons, Team},
+    utils::{
+        ImageType,
+        fetch::post_bytes,
+        images::{champion_images, preload},
     },
-    components::{image::Image, tables::header::TableHeader},
-    model::{Dragons, SimpleStats},
-    utils::{ImageType, fetch::post_bytes},
 };
-use std::{cell::RefCell, rc::Rc};
+use std::rc::Rc;
 use web_sys::AbortController;
 use yew::{platform::spawn_local, prelude::*};
 
 #[derive(Clone, PartialEq)]
 pub struct PlayerProps {
     pub player: UseReducerHandle<Player>,
-    pub last_action: Rc<RefCell<LastAction>>,
 }
 
 #[derive(Clone, PartialEq)]
 pub struct EnemyProps {
     pub enemies: UseReducerHandle<Enemies>,
     pub enemy_index: UseStateHandle<usize>,
-    pub last_action: Rc<RefCell<LastAction>>,
 }
 
 #[component]
 pub fn Calculator() -> Html {
     let player = use_reducer(Player::default);
     let enemies = use_reducer(Enemies::default);
+    let allies = use_reducer(Allies::default);
+    let team = use_state(|| Team::Blue);
     let dragons = use_reducer(Dragons::default);
 
-    let game_data = use_state(|| None::<Game>);
+    // Game along with the identifiers of the enemies sent with its request
+    let game_data = use_state(|| None::<(Rc<Game>, Rc<[EnemyId]>)>);
     let controller = use_state(|| None::<AbortController>);
-    let last_action = use_mut_ref(|| LastAction::Init);
+    let inferred = use_state(InferredStats::default);
+    let pin = use_context::<UseReducerDispatcher<PinnedFormulas>>();
 
     {
         let game_data = game_data.clone();
@@ -41,17 +62,22 @@ pub fn Calculator() -> Html {
         let player = player.clone();
         let enemies = enemies.clone();
         let dragons = dragons.clone();
-        let last_action = last_action.clone();
-        use_effect_with((player.clone(), enemies.clone()), move |_| {
-            if *last_action.borrow() == LastAction::Replace {
-                last_action.replace(LastAction::Any);
-                return;
-            };
-
+        let inferred = inferred.clone();
+        let pin = pin.clone();
+        let deps = (player.clone(), enemies.clone(), dragons.clone());
+        use_effect_with(deps, move |_| {
             if let Some(controller) = &*controller {
                 controller.abort();
             }
 
+            let valid_builds = validate_build(&player.data.items).is_empty()
+                && enemies
+                    .iter()
+                    .all(|enemy| validate_build(&enemy.items).is_empty());
+            if !valid_builds {
+                return;
+            }
+
             let new_controller = AbortController::new().ok();
             let signal = new_controller.as_ref().map(|c| c.signal());
             controller.set(new_controller);
@@ -65,35 +91,16 @@ pub fn Calculator() -> Html {
 
                 match post_bytes::<Game>("/api/games/calculator", &input_game, signal).await {
                     Ok(data) => {
-                        let infer_enemy_player_stats = |index| {
-                            let enemy: &Rc<PlayerData<SimpleStats>> = &enemies[index];
-                            if enemy.infer_stats {
-                                last_action.replace(LastAction::Replace);
-                                enemies.dispatch(EnemyAction::Change(
-                                    index,
-                                    DataAction::Stats(&enemy.stats as _),
-                                ));
-                            }
-                        };
-                        let action = *last_action.borrow();
-                        match action {
-                            LastAction::Init | LastAction::CurrentPlayer => {
-                                if player.data.infer_stats {
-                                    last_action.replace(LastAction::Replace);
-                                    player.dispatch(PlayerAction::Data(DataAction::Stats(
-                                        &data.current_player.current_stats as _,
-                                    )));
-                                }
-                                if action == LastAction::Init {
-                                    (0..data.enemies.len())
-                                        .into_iter()
-                                        .for_each(infer_enemy_player_stats);
-                                }
-                            }
-                            LastAction::EnemyPlayer(index) => infer_enemy_player_stats(index),
-                            _ => {}
-                        };
-                        game_data.set(Some(data));
+                        inferred.set(InferredStats::new(&data, &player, &enemies));
+                        let data = Rc::new(data);
+                        if let Some(pin) = &pin {
+                            pin.dispatch(PinAction::Game(
+                                data.clone(),
+                                player.abilities,
+                                enemies.ids().to_vec(),
+                            ));
+                        }
+                        game_data.set(Some((data, enemies.ids().into())));
                     }
                     Err(e) => web_sys::console::error_1(
                         &format!("Failed to request calculator api: {e:?}").into(),
@@ -103,39 +110,73 @@ pub fn Calculator() -> Html {
         });
     }
 
+    // Icons of the current build are downloaded ahead of time, since most of
+    // them are displayed again once the game is calculated
+    use_effect_with((player.clone(), enemies.clone()), |(player, enemies)| {
+        let items = std::iter::once(&player.data.items)
+            .chain(enemies.iter().map(|enemy| &enemy.items))
+            .flatten()
+            .map(|item_id| ImageType::Item(*item_id));
+        let runes = player.runes.iter().map(|rune_id| ImageType::Rune(*rune_id));
+        let champions = enemies
+            .iter()
+            .map(|enemy| ImageType::from(enemy.champion_id));
+        preload(
+            champion_images(player.data.champion_id)
+                .chain(items)
+                .chain(runes)
+                .chain(champions),
+        );
+    });
+
     let player_props = PlayerProps {
         player: player.clone(),
-        last_action: last_action.clone(),
+    };
+
+    let input_game = InputGame {
+        active_player: (*player).clone(),
+        enemy_players: (*enemies).to_vec(),
+        dragons: *dragons,
     };
 
     html! {
         <div>
-            <PlayerInput {player_props} />
+            <div class={classes!("flex", "gap-4")}>
+                <ImageModeToggle />
+                <OfflineToggle />
+            </div>
+            <CommandPalette
+                player={player.clone()}
+                enemies={enemies.clone()}
+                dragons={dragons.clone()}
+            />
+            <LiveGameInput
+                player={player.clone()}
+                allies={allies.clone()}
+                enemies={enemies.clone()}
+                dragons={dragons.clone()}
+                team={team.clone()}
+            />
+            <TeamsPanel
+                player={player.clone()}
+                allies={allies.clone()}
+                enemies={enemies.clone()}
+                team={team.clone()}
+            />
+            <PlayerInput {player_props} inferred={inferred.player} />
+            <EnemiesInput enemies={enemies.clone()} inferred={(*inferred).clone()} />
+            <SweepPanel {input_game} />
+            <InspectorPanel />
             {match *game_data {
-                Some(ref data) => {
-                    let Game {
-                        monster_damages,
-                        current_player,
-                        enemies,
-                        tower_damages,
-                        abilities_meta,
-                        abilities_to_merge,
-                        items_meta,
-                        runes_meta
-                    } = data;
+                Some((ref data, ref enemy_ids)) => {
+                    let champion_id = data.current_player.champion_id;
                     html! {
                         <div>
-                            <Image src={ImageType::from(current_player.champion_id)} />
-                            <span>{ current_player.champion_id.name() }</span>
-                            <div class={classes!("flex", "gap-4")}>
-                            <TableHeader
-                                champion_id={current_player.champion_id}
-                                abilities_to_merge={abilities_to_merge.clone()}
-                                abilities_meta={abilities_meta.clone()}
-                                items_meta={items_meta.clone()}
-                                runes_meta={runes_meta.clone()}
-                            />
-                            </div>
+                            <Image src={ImageType::from(champion_id)} focusable={true} />
+                            <span>{ champion_id.name() }</span>
+                            <ExportPanel game={data.clone()} />
+                            <DamageTable game={data.clone()} />
+                            <AoePanel game={data.clone()} enemy_ids={enemy_ids.clone()} />
                         </div>
                     }
                 },
@@ -1,5 +1,6 @@
 use crate::{
     calculator::{AbilityLevels, Player, PlayerData},
+    data::items::validate_insert,
     model::{Dragons, SimpleStats, Stats, ValueException},
 };
 use std::rc::Rc;
@@ -12,71 +13,207 @@ pub type PlayerDataAction = DataAction<Stats>;
 pub enum PlayerAction {
     InsertRune(RuneId),
     RemoveRune(usize),
+    SetRunes(Vec<RuneId>),
+    /// Sets the stacks of a rune, replacing its previous exception if any.
+    /// Exceptions are identified by their rune rather than by their index, so
+    /// actions dispatched from an earlier render never edit another exception
     InsertRuneExc(RuneId, u32),
-    RemoveRuneExc(usize),
+    /// Sets the stacks of a rune, ignored if it has no exception
+    UpdateRuneExc(RuneId, u32),
+    RemoveRuneExc(RuneId),
     Data(PlayerDataAction),
     AbilityLevel(AbilityLevels),
+    Load(Box<Player>),
 }
 
 pub enum DataAction<T> {
-    Stats(*const T),
+    /// Replaces the stats entered for this player, which are only used while
+    /// `infer_stats` is disabled. Inferred stats are never stored in the input,
+    /// see [`crate::calculator::inferred`]
+    Stats(T),
     Stacks(u32),
+    Level(u8),
     InferStats(bool),
     IsMegaGnar(bool),
+    /// Appends an item if it can be held with the current build. Items that can
+    /// not be held are ignored without feedback, so callers must check them with
+    /// [`validate_insert`] first and show the error to the user
     InsertItem(ItemId),
+    /// Replaces every item. Unlike [`DataAction::InsertItem`], the build is not
+    /// validated, since it comes from the game client
+    SetItems(Vec<ItemId>),
     RemoveItem(usize),
+    /// Moves the item at the first index to the second one
+    MoveItem(usize, usize),
     ChampionId(ChampionId),
+    /// Sets the stacks of an item, replacing its previous exception if any.
+    /// See [`PlayerAction::InsertRuneExc`]
     InsertItemExc(ItemId, u32),
-    RemoveItemExc(usize),
+    /// Sets the stacks of an item, ignored if it has no exception
+    UpdateItemExc(ItemId, u32),
+    RemoveItemExc(ItemId),
 }
 
 pub enum EnemyAction {
-    Insert,
-    Remove(usize),
-    Change(usize, EnemyDataAction),
+    Insert(PlayerData<SimpleStats>),
+    Load(Vec<PlayerData<SimpleStats>>),
+    Remove(EnemyId),
+    /// Moves the player to the given index
+    Move(EnemyId, usize),
+    Change(EnemyId, EnemyDataAction),
 }
 
+/// Identifier of a player in [`Enemies`] that does not change while the player
+/// is in the list, unlike its index, which shifts when other players are removed
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct EnemyId(u32);
+
+/// List of players that are not the active player. The same type also holds
+/// the allies of the active player, see [`Allies`]
 #[derive(Clone, Default, PartialEq)]
-#[repr(transparent)]
-pub struct Enemies(Vec<Rc<PlayerData<SimpleStats>>>);
+pub struct Enemies {
+    players: Vec<Rc<PlayerData<SimpleStats>>>,
+    ids: Vec<EnemyId>,
+    next_id: u32,
+}
+
+/// Allies share the storage and actions of [`Enemies`], but are not sent to the
+/// calculator unless one of them is promoted to active player
+pub type Allies = Enemies;
 
 impl core::ops::Deref for Enemies {
     type Target = Vec<Rc<PlayerData<SimpleStats>>>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.players
+    }
+}
+
+impl Enemies {
+    /// Identifiers of the players, in the same order as the players
+    pub fn ids(&self) -> &[EnemyId] {
+        &self.ids
+    }
+
+    pub fn id(&self, index: usize) -> Option<EnemyId> {
+        self.ids.get(index).copied()
+    }
+
+    pub fn index_of(&self, id: EnemyId) -> Option<usize> {
+        self.ids.iter().position(|v| *v == id)
+    }
+
+    /// Actions that turn the list into `players`, as read from a running game.
+    /// Players are matched by champion, so the ones that are still in the game
+    /// keep their [`EnemyId`] and every field that the game does not provide.
+    /// Only the changed fields are updated, and players are only inserted or
+    /// removed when the roster changes
+    pub fn sync(&self, players: Vec<PlayerData<SimpleStats>>) -> Vec<EnemyAction> {
+        let mut unmatched = self
+            .ids
+            .iter()
+            .copied()
+            .zip(self.iter())
+            .collect::<Vec<_>>();
+        let mut actions = Vec::new();
+        for player in players {
+            let Some(i) = unmatched
+                .iter()
+                .position(|(_, other)| other.champion_id == player.champion_id)
+            else {
+                actions.push(EnemyAction::Insert(player));
+                continue;
+            };
+            let (id, other) = unmatched.remove(i);
+            if other.level != player.level {
+                actions.push(EnemyAction::Change(id, DataAction::Level(player.level)));
+            }
+            if other.infer_stats != player.infer_stats {
+                let action = DataAction::InferStats(player.infer_stats);
+                actions.push(EnemyAction::Change(id, action));
+            }
+            if other.items != player.items {
+                actions.push(EnemyAction::Change(id, DataAction::SetItems(player.items)));
+            }
+        }
+        actions.extend(unmatched.into_iter().map(|(id, _)| EnemyAction::Remove(id)));
+        actions
+    }
+
+    fn push(&mut self, player: PlayerData<SimpleStats>) {
+        self.players.push(Rc::new(player));
+        self.ids.push(EnemyId(self.next_id));
+        self.next_id += 1;
     }
 }
 
-impl core::ops::DerefMut for Enemies {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+/// Removes the element at `index`, preserving the order of the others. Indexes
+/// may come from an earlier render, so out of bounds ones are ignored
+fn remove_element<T>(vec: &mut Vec<T>, index: usize) {
+    if index < vec.len() {
+        vec.remove(index);
+    }
+}
+
+/// Moves the element at `from` to `to`, shifting the ones in between.
+/// Out of bounds indexes are ignored
+fn move_element<T>(vec: &mut Vec<T>, from: usize, to: usize) {
+    if from < vec.len() && to < vec.len() {
+        let value = vec.remove(from);
+        vec.insert(to, value);
     }
 }
 
 impl<T: Copy> PlayerData<T> {
+    fn item_exception(&mut self, item_id: ItemId) -> Option<&mut ValueException> {
+        self.item_exceptions
+            .iter_mut()
+            .find(|exc| exc.get_item_id() == Some(item_id))
+    }
+
     pub fn reduce_mut(&mut self, action: DataAction<T>) {
         match action {
-            DataAction::Stats(v) => self.stats = unsafe { *v },
+            DataAction::Stats(v) => self.stats = v,
             DataAction::Stacks(v) => self.stacks = v,
+            DataAction::Level(v) => self.level = v,
             DataAction::InferStats(v) => self.infer_stats = v,
             DataAction::IsMegaGnar(v) => self.is_mega_gnar = v,
-            DataAction::InsertItem(v) => self.items.push(v),
-            DataAction::ChampionId(v) => self.champion_id = v,
-            DataAction::RemoveItem(v) => {
-                self.items.swap_remove(v);
+            DataAction::InsertItem(v) => {
+                if validate_insert(&self.items, v).is_ok() {
+                    self.items.push(v);
+                }
             }
+            DataAction::SetItems(v) => self.items = v,
+            DataAction::ChampionId(v) => self.champion_id = v,
+            DataAction::RemoveItem(v) => remove_element(&mut self.items, v),
+            DataAction::MoveItem(from, to) => move_element(&mut self.items, from, to),
             DataAction::InsertItemExc(item_id, stacks) => {
                 let value = ValueException::pack_item_id(item_id, stacks);
-                self.item_exceptions.push(value)
+                match self.item_exception(item_id) {
+                    Some(exc) => *exc = value,
+                    None => self.item_exceptions.push(value),
+                }
             }
-            DataAction::RemoveItemExc(v) => {
-                self.item_exceptions.swap_remove(v);
+            DataAction::UpdateItemExc(item_id, stacks) => {
+                if let Some(exc) = self.item_exception(item_id) {
+                    *exc = exc.with_stacks(stacks);
+                }
             }
+            DataAction::RemoveItemExc(item_id) => self
+                .item_exceptions
+                .retain(|exc| exc.get_item_id() != Some(item_id)),
         }
     }
 }
 
+impl Player {
+    fn rune_exception(&mut self, rune_id: RuneId) -> Option<&mut ValueException> {
+        self.rune_exceptions
+            .iter_mut()
+            .find(|exc| exc.get_rune_id() == Some(rune_id))
+    }
+}
+
 impl Reducible for Player {
     type Action = PlayerAction;
 
@@ -84,17 +221,25 @@ impl Reducible for Player {
         let mut new = (*self).clone();
         match action {
             Self::Action::InsertRune(v) => new.runes.push(v),
+            Self::Action::SetRunes(v) => new.runes = v,
             Self::Action::AbilityLevel(v) => new.abilities = v,
-            Self::Action::RemoveRune(v) => {
-                new.runes.swap_remove(v);
-            }
+            Self::Action::Load(v) => new = *v,
+            Self::Action::RemoveRune(v) => remove_element(&mut new.runes, v),
             Self::Action::InsertRuneExc(rune_id, stacks) => {
                 let value = ValueException::pack_rune_id(rune_id, stacks);
-                new.rune_exceptions.push(value);
+                match new.rune_exception(rune_id) {
+                    Some(exc) => *exc = value,
+                    None => new.rune_exceptions.push(value),
+                }
             }
-            Self::Action::RemoveRuneExc(v) => {
-                new.rune_exceptions.swap_remove(v);
+            Self::Action::UpdateRuneExc(rune_id, stacks) => {
+                if let Some(exc) = new.rune_exception(rune_id) {
+                    *exc = exc.with_stacks(stacks);
+                }
             }
+            Self::Action::RemoveRuneExc(rune_id) => new
+                .rune_exceptions
+                .retain(|exc| exc.get_rune_id() != Some(rune_id)),
             Self::Action::Data(v) => {
                 let mut data = new.data;
                 data.reduce_mut(v);
@@ -121,10 +266,28 @@ impl Reducible for Enemies {
     fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
         let mut new = (*self).clone();
         match action {
-            EnemyAction::Insert => new.push(Default::default()),
-            EnemyAction::Change(v, action) => new[v] = new[v].clone().reduce(action),
-            EnemyAction::Remove(v) => {
-                new.swap_remove(v);
+            EnemyAction::Insert(v) => new.push(v),
+            EnemyAction::Load(v) => {
+                new.players.clear();
+                new.ids.clear();
+                v.into_iter().for_each(|player| new.push(player));
+            }
+            EnemyAction::Change(id, action) => {
+                if let Some(i) = new.index_of(id) {
+                    new.players[i] = new.players[i].clone().reduce(action);
+                }
+            }
+            EnemyAction::Remove(id) => {
+                if let Some(i) = new.index_of(id) {
+                    new.players.remove(i);
+                    new.ids.remove(i);
+                }
+            }
+            EnemyAction::Move(id, to) => {
+                if let Some(from) = new.index_of(id) {
+                    move_element(&mut new.players, from, to);
+                    move_element(&mut new.ids, from, to);
+                }
             }
         }
         Rc::new(new)
@@ -136,6 +299,7 @@ pub enum DragonAction {
     AllyEarth(u16),
     AllyChemtech(u16),
     EnemyEarth(u16),
+    Load(Dragons),
 }
 
 impl Reducible for Dragons {
@@ -148,34 +312,322 @@ impl Reducible for Dragons {
             DragonAction::AllyEarth(v) => new.ally_earth_dragons = v,
             DragonAction::AllyChemtech(v) => new.ally_chemtech_dragons = v,
             DragonAction::EnemyEarth(v) => new.enemy_earth_dragons = v,
+            DragonAction::Load(v) => new = v,
         }
         Rc::new(new)
     }
 }
 
-#[derive(PartialEq, Clone, Copy)]
-pub enum LastAction {
-    Init,
-    Any,
-    CurrentPlayer,
-    EnemyPlayer(usize),
-    Replace,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{
+        items::{MAX_ITEMS, all_items},
+        runes::all_runes,
+    };
 
-impl PlayerAction {
-    pub const fn action(&self) -> LastAction {
-        match self {
-            Self::Data(DataAction::Stats(_)) => LastAction::Replace,
-            _ => LastAction::CurrentPlayer,
-        }
+    /// First `n` items that can be held together
+    fn build(n: usize) -> Vec<ItemId> {
+        all_items().fold(Vec::new(), |mut items, item_id| {
+            if items.len() < n && validate_insert(&items, item_id).is_ok() {
+                items.push(item_id);
+            }
+            items
+        })
     }
-}
 
-impl EnemyDataAction {
-    pub const fn action(&self, i: usize) -> LastAction {
-        match self {
-            Self::Stats(_) => LastAction::Replace,
-            _ => LastAction::EnemyPlayer(i),
+    fn rune(n: usize) -> RuneId {
+        all_runes().nth(n).unwrap()
+    }
+
+    fn reduce_player(player: Player, action: PlayerAction) -> Player {
+        (*Rc::new(player).reduce(action)).clone()
+    }
+
+    fn reduce_data(
+        data: PlayerData<SimpleStats>,
+        action: EnemyDataAction,
+    ) -> PlayerData<SimpleStats> {
+        (*Rc::new(data).reduce(action)).clone()
+    }
+
+    fn reduce_enemies(enemies: Enemies, action: EnemyAction) -> Enemies {
+        (*Rc::new(enemies).reduce(action)).clone()
+    }
+
+    fn enemy(champion_id: ChampionId) -> PlayerData<SimpleStats> {
+        PlayerData {
+            champion_id,
+            ..Default::default()
         }
     }
+
+    fn champions(enemies: &Enemies) -> Vec<ChampionId> {
+        enemies.iter().map(|enemy| enemy.champion_id).collect()
+    }
+
+    #[test]
+    fn player_runes() {
+        let player = reduce_player(Player::default(), PlayerAction::InsertRune(rune(0)));
+        let player = reduce_player(player, PlayerAction::InsertRune(rune(1)));
+        assert_eq!(player.runes, [rune(0), rune(1)]);
+
+        let player = reduce_player(player, PlayerAction::RemoveRune(0));
+        assert_eq!(player.runes, [rune(1)]);
+        let player = reduce_player(player, PlayerAction::RemoveRune(1));
+        assert_eq!(player.runes, [rune(1)]);
+
+        let player = reduce_player(player, PlayerAction::SetRunes(vec![rune(2), rune(0)]));
+        assert_eq!(player.runes, [rune(2), rune(0)]);
+    }
+
+    #[test]
+    fn player_rune_exceptions() {
+        let player = reduce_player(Player::default(), PlayerAction::InsertRuneExc(rune(0), 3));
+        assert_eq!(player.rune_exceptions[0].get_rune_id(), Some(rune(0)));
+        assert_eq!(player.rune_exceptions[0].stacks(), 3);
+
+        let player = reduce_player(player, PlayerAction::UpdateRuneExc(rune(0), 7));
+        assert_eq!(player.rune_exceptions[0].get_rune_id(), Some(rune(0)));
+        assert_eq!(player.rune_exceptions[0].stacks(), 7);
+
+        // Runes hold a single exception, and missing ones are ignored
+        let player = reduce_player(player, PlayerAction::InsertRuneExc(rune(0), 4));
+        let player = reduce_player(player, PlayerAction::UpdateRuneExc(rune(1), 2));
+        let player = reduce_player(player, PlayerAction::RemoveRuneExc(rune(1)));
+        assert_eq!(player.rune_exceptions.len(), 1);
+        assert_eq!(player.rune_exceptions[0].stacks(), 4);
+
+        let player = reduce_player(player, PlayerAction::RemoveRuneExc(rune(0)));
+        assert!(player.rune_exceptions.is_empty());
+    }
+
+    #[test]
+    fn player_abilities_data_and_load() {
+        let abilities = AbilityLevels {
+            q: 5,
+            w: 1,
+            e: 3,
+            r: 2,
+        };
+        let player = reduce_player(Player::default(), PlayerAction::AbilityLevel(abilities));
+        assert_eq!(player.abilities, abilities);
+
+        let player = reduce_player(player, PlayerAction::Data(DataAction::Level(11)));
+        assert_eq!(player.data.level, 11);
+
+        let stats = Stats {
+            ability_power: 120,
+            armor: 40,
+            ..Default::default()
+        };
+        let player = reduce_player(player, PlayerAction::Data(DataAction::Stats(stats)));
+        assert_eq!(player.data.stats, stats);
+
+        let mut loaded = Player::default();
+        loaded.data.champion_id = ChampionId::Zed;
+        loaded.runes = vec![rune(1)];
+        let player = reduce_player(player, PlayerAction::Load(Box::new(loaded.clone())));
+        assert_eq!(player, loaded);
+    }
+
+    #[test]
+    fn data_fields() {
+        let stats = SimpleStats {
+            armor: 30,
+            health: 900,
+            magic_resist: 35,
+        };
+        let data = reduce_data(PlayerData::default(), DataAction::Stats(stats));
+        assert_eq!(data.stats, stats);
+
+        let data = reduce_data(data, DataAction::Stacks(4));
+        let data = reduce_data(data, DataAction::Level(13));
+        let data = reduce_data(data, DataAction::InferStats(true));
+        let data = reduce_data(data, DataAction::IsMegaGnar(true));
+        let data = reduce_data(data, DataAction::ChampionId(ChampionId::Gnar));
+        assert_eq!(data.stacks, 4);
+        assert_eq!(data.level, 13);
+        assert!(data.infer_stats);
+        assert!(data.is_mega_gnar);
+        assert_eq!(data.champion_id, ChampionId::Gnar);
+    }
+
+    #[test]
+    fn data_items() {
+        let items = build(3);
+        let data = items.iter().fold(PlayerData::default(), |data, item_id| {
+            reduce_data(data, DataAction::InsertItem(*item_id))
+        });
+        assert_eq!(data.items, items);
+
+        // Items are ignored once the inventory is full
+        let full = build(MAX_ITEMS);
+        let data = reduce_data(
+            PlayerData {
+                items: full.clone(),
+                ..Default::default()
+            },
+            DataAction::InsertItem(items[0]),
+        );
+        assert_eq!(data.items, full);
+
+        let data = items.iter().fold(PlayerData::default(), |data, item_id| {
+            reduce_data(data, DataAction::InsertItem(*item_id))
+        });
+        let data = reduce_data(data, DataAction::MoveItem(0, 2));
+        assert_eq!(data.items, [items[1], items[2], items[0]]);
+
+        // Out of bounds moves are ignored
+        let data = reduce_data(data, DataAction::MoveItem(0, 3));
+        assert_eq!(data.items, [items[1], items[2], items[0]]);
+
+        let data = reduce_data(data, DataAction::RemoveItem(1));
+        assert_eq!(data.items, [items[1], items[0]]);
+
+        // Out of bounds removals are ignored
+        let data = reduce_data(data, DataAction::RemoveItem(2));
+        assert_eq!(data.items, [items[1], items[0]]);
+    }
+
+    #[test]
+    fn data_item_exceptions() {
+        let item_id = build(1)[0];
+        let data = reduce_data(PlayerData::default(), DataAction::InsertItemExc(item_id, 2));
+        assert_eq!(data.item_exceptions[0].get_item_id(), Some(item_id));
+        assert_eq!(data.item_exceptions[0].stacks(), 2);
+
+        let data = reduce_data(data, DataAction::UpdateItemExc(item_id, 9));
+        assert_eq!(data.item_exceptions[0].get_item_id(), Some(item_id));
+        assert_eq!(data.item_exceptions[0].stacks(), 9);
+
+        let data = reduce_data(data, DataAction::InsertItemExc(item_id, 1));
+        assert_eq!(data.item_exceptions.len(), 1);
+        assert_eq!(data.item_exceptions[0].stacks(), 1);
+
+        let data = reduce_data(data, DataAction::RemoveItemExc(item_id));
+        assert!(data.item_exceptions.is_empty());
+        let data = reduce_data(data, DataAction::UpdateItemExc(item_id, 9));
+        assert!(data.item_exceptions.is_empty());
+    }
+
+    #[test]
+    fn enemies_insert_and_change() {
+        let enemies = reduce_enemies(
+            Enemies::default(),
+            EnemyAction::Insert(enemy(ChampionId::Ahri)),
+        );
+        let enemies = reduce_enemies(enemies, EnemyAction::Insert(enemy(ChampionId::Zed)));
+        assert_eq!(champions(&enemies), [ChampionId::Ahri, ChampionId::Zed]);
+        assert_ne!(enemies.id(0), enemies.id(1));
+
+        let zed = enemies.id(1).unwrap();
+        let enemies = reduce_enemies(enemies, EnemyAction::Change(zed, DataAction::Level(7)));
+        assert_eq!(enemies[1].level, 7);
+        assert_eq!(enemies[0].level, 0);
+    }
+
+    #[test]
+    fn enemies_remove_and_move_by_id() {
+        let enemies = [ChampionId::Ahri, ChampionId::Zed, ChampionId::Garen]
+            .into_iter()
+            .fold(Enemies::default(), |enemies, champion_id| {
+                reduce_enemies(enemies, EnemyAction::Insert(enemy(champion_id)))
+            });
+        let [ahri, zed, garen] = [0, 1, 2].map(|i| enemies.id(i).unwrap());
+
+        let enemies = reduce_enemies(enemies, EnemyAction::Move(ahri, 2));
+        assert_eq!(
+            champions(&enemies),
+            [ChampionId::Zed, ChampionId::Garen, ChampionId::Ahri]
+        );
+        assert_eq!(enemies.ids(), [zed, garen, ahri]);
+
+        // Identifiers keep pointing to the same player after a removal
+        let enemies = reduce_enemies(enemies, EnemyAction::Remove(zed));
+        assert_eq!(champions(&enemies), [ChampionId::Garen, ChampionId::Ahri]);
+        let enemies = reduce_enemies(enemies, EnemyAction::Change(ahri, DataAction::Stacks(2)));
+        assert_eq!(enemies[1].stacks, 2);
+
+        // Unknown identifiers are ignored
+        let enemies = reduce_enemies(enemies, EnemyAction::Remove(zed));
+        assert_eq!(enemies.len(), 2);
+    }
+
+    #[test]
+    fn enemies_load() {
+        let enemies = reduce_enemies(
+            Enemies::default(),
+            EnemyAction::Insert(enemy(ChampionId::Ahri)),
+        );
+        let previous = enemies.id(0).unwrap();
+        let enemies = reduce_enemies(
+            enemies,
+            EnemyAction::Load(vec![enemy(ChampionId::Zed), enemy(ChampionId::Garen)]),
+        );
+        assert_eq!(champions(&enemies), [ChampionId::Zed, ChampionId::Garen]);
+        assert_eq!(enemies.ids().len(), 2);
+        assert!(!enemies.ids().contains(&previous));
+    }
+
+    #[test]
+    fn enemies_keep_ids_across_live_refreshes() {
+        let enemies = [ChampionId::Zed, ChampionId::Ahri]
+            .into_iter()
+            .fold(Enemies::default(), |enemies, champion_id| {
+                reduce_enemies(enemies, EnemyAction::Insert(enemy(champion_id)))
+            });
+        let [zed, ahri] = [0, 1].map(|i| enemies.id(i).unwrap());
+        let enemies = reduce_enemies(enemies, EnemyAction::Change(zed, DataAction::Stacks(5)));
+
+        let refresh = |enemies: &Enemies, players: Vec<PlayerData<SimpleStats>>| {
+            enemies
+                .sync(players)
+                .into_iter()
+                .fold(enemies.clone(), reduce_enemies)
+        };
+        let leveled = PlayerData {
+            level: 9,
+            ..enemy(ChampionId::Zed)
+        };
+        let enemies = refresh(&enemies, vec![enemy(ChampionId::Ahri), leveled.clone()]);
+        assert_eq!(enemies.ids(), [zed, ahri]);
+        assert_eq!(enemies[0].level, 9);
+        assert_eq!(enemies[0].stacks, 5);
+
+        // Refreshing with the same data changes nothing
+        assert!(
+            enemies
+                .sync(vec![enemy(ChampionId::Ahri), leveled.clone()])
+                .is_empty()
+        );
+
+        // Only the players that left or joined the game change their ids
+        let enemies = refresh(&enemies, vec![leveled, enemy(ChampionId::Garen)]);
+        assert_eq!(champions(&enemies), [ChampionId::Zed, ChampionId::Garen]);
+        assert_eq!(enemies.id(0), Some(zed));
+        assert!(enemies.index_of(ahri).is_none());
+    }
+
+    #[test]
+    fn dragons() {
+        let reduce = |dragons: Dragons, action| *Rc::new(dragons).reduce(action);
+        let dragons = reduce(Dragons::default(), DragonAction::AllyFire(1));
+        let dragons = reduce(dragons, DragonAction::AllyEarth(2));
+        let dragons = reduce(dragons, DragonAction::AllyChemtech(3));
+        let dragons = reduce(dragons, DragonAction::EnemyEarth(4));
+        assert_eq!(
+            dragons,
+            Dragons {
+                ally_fire_dragons: 1,
+                ally_earth_dragons: 2,
+                ally_chemtech_dragons: 3,
+                enemy_earth_dragons: 4,
+            }
+        );
+        assert_eq!(
+            reduce(dragons, DragonAction::Load(Dragons::default())),
+            Dragons::default()
+        );
+    }
 }
@@ -0,0 +1,297 @@
+use crate::{
+    calculator::{AbilityLevels, AbilitySlot, Game, InputGame, columns::ability_columns},
+    utils::fetch::post_bytes,
+};
+use bincode::{Decode, Encode};
+use std::{error::Error, fmt::Write, ops::RangeInclusive};
+use tutorlolv2_gen::ChampionId;
+use web_sys::AbortSignal;
+
+/// Maximum level a champion can reach
+pub const MAX_LEVEL: u8 = 18;
+
+/// Colors used to draw each series of the chart, cycled when there are more series
+const PALETTE: [&str; 8] = [
+    "#60a5fa", "#f87171", "#4ade80", "#facc15", "#c084fc", "#fb923c", "#2dd4bf", "#f472b6",
+];
+
+/// Escapes the characters that can't appear as is in the text or attributes
+/// of an XML document, since labels may hold any character
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Which value of the input is changed between each point of the sweep
+#[derive(Clone, Copy, Debug, Decode, Encode, PartialEq)]
+pub enum SweepAxis {
+    /// Varies [`crate::calculator::PlayerData::level`], keeping the ability ranks
+    Level,
+    /// Varies the rank of a single ability, keeping everything else
+    Rank(AbilitySlot),
+    /// Varies the level, and ranks abilities up following [`AbilityLevels::at_level`]
+    LevelWithRanks,
+}
+
+impl SweepAxis {
+    pub const ALL: [Self; 6] = [
+        Self::Level,
+        Self::LevelWithRanks,
+        Self::Rank(AbilitySlot::Q),
+        Self::Rank(AbilitySlot::W),
+        Self::Rank(AbilitySlot::E),
+        Self::Rank(AbilitySlot::R),
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Level => String::from("Level"),
+            Self::LevelWithRanks => String::from("Level (auto ranks)"),
+            Self::Rank(slot) => format!("{} rank", slot.as_char()),
+        }
+    }
+
+    pub const fn points(self) -> RangeInclusive<u8> {
+        match self {
+            Self::Level | Self::LevelWithRanks => 1..=MAX_LEVEL,
+            Self::Rank(slot) => 1..=slot.max_rank(),
+        }
+    }
+
+    /// Modifies `input` so it represents the point `x` of this axis
+    pub fn apply(self, input: &mut InputGame, x: u8) {
+        let player = &mut input.active_player;
+        match self {
+            Self::Level => player.data.level = x,
+            Self::Rank(slot) => player.abilities.set(slot, x),
+            Self::LevelWithRanks => {
+                player.data.level = x;
+                player.abilities = AbilityLevels::at_level(x);
+            }
+        }
+    }
+}
+
+/// Damage values of a single ability column, one for each point of the sweep.
+/// Only the label of the column is kept, since its indexes are only valid
+/// for the [`Game`] they were created from
+#[derive(Clone, Debug, Decode, Encode, PartialEq)]
+pub struct SweepSeries {
+    pub label: String,
+    pub values: Vec<i32>,
+}
+
+#[derive(Clone, Debug, Decode, Encode, PartialEq)]
+pub struct SweepResult {
+    pub axis: SweepAxis,
+    pub champion_id: ChampionId,
+    pub enemy_id: ChampionId,
+    pub points: Vec<u8>,
+    pub series: Vec<SweepSeries>,
+}
+
+/// Recalculates the game once for each point of `axis`, collecting the damage
+/// of every ability against the enemy at `enemy_index`. Since stats change with
+/// the level, the active player always has its stats inferred during the sweep.
+/// `on_progress` is called with the number of completed points and the total
+pub async fn run_sweep(
+    mut input: InputGame,
+    axis: SweepAxis,
+    enemy_index: usize,
+    signal: Option<AbortSignal>,
+    on_progress: impl Fn(usize, usize),
+) -> Result<SweepResult, Box<dyn Error>> {
+    input.active_player.data.infer_stats = true;
+
+    let points = axis.points().collect::<Vec<_>>();
+    let mut columns = Vec::new();
+    let mut result = SweepResult {
+        axis,
+        champion_id: input.active_player.data.champion_id,
+        enemy_id: input
+            .enemy_players
+            .get(enemy_index)
+            .ok_or("Selected enemy does not exist")?
+            .champion_id,
+        points: points.clone(),
+        series: Vec::new(),
+    };
+
+    for (i, &x) in points.iter().enumerate() {
+        axis.apply(&mut input, x);
+        let game = post_bytes::<Game>("/api/games/calculator", &input, signal.clone()).await?;
+        let enemy = game
+            .enemies
+            .get(enemy_index)
+            .ok_or("Selected enemy is missing from the response")?;
+
+        if columns.is_empty() {
+            columns = ability_columns(&game.abilities_meta, &game.abilities_to_merge);
+            result.series = columns
+                .iter()
+                .map(|column| SweepSeries {
+                    label: column.label(),
+                    values: Vec::with_capacity(points.len()),
+                })
+                .collect();
+        }
+
+        for (column, series) in columns.iter().zip(result.series.iter_mut()) {
+            let (_, maximum) = column.range(&enemy.damages);
+            series.values.push(maximum);
+        }
+
+        on_progress(i + 1, points.len());
+    }
+
+    Ok(result)
+}
+
+impl SweepResult {
+    pub const CHART_WIDTH: u32 = 720;
+    pub const CHART_HEIGHT: u32 = 400;
+
+    pub fn file_name(&self, extension: &str) -> String {
+        format!(
+            "{:?}_vs_{:?}_{}.{extension}",
+            self.champion_id,
+            self.enemy_id,
+            self.axis.label().to_lowercase().replace(' ', "_")
+        )
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = self.axis.label();
+        for series in &self.series {
+            let _ = write!(csv, ",{}", series.label);
+        }
+        for (i, x) in self.points.iter().enumerate() {
+            let _ = write!(csv, "\n{x}");
+            for series in &self.series {
+                let _ = write!(
+                    csv,
+                    ",{}",
+                    series.values.get(i).copied().unwrap_or_default()
+                );
+            }
+        }
+        csv
+    }
+
+    /// Renders the damage curves as a standalone SVG document. The same string is
+    /// displayed in the page and exported, so both always look the same
+    pub fn to_svg(&self) -> String {
+        const LEFT: f64 = 56.0;
+        const RIGHT: f64 = 160.0;
+        const TOP: f64 = 24.0;
+        const BOTTOM: f64 = 40.0;
+        const TICKS: i32 = 5;
+
+        let width = Self::CHART_WIDTH as f64;
+        let height = Self::CHART_HEIGHT as f64;
+        let plot_width = width - LEFT - RIGHT;
+        let plot_height = height - TOP - BOTTOM;
+
+        let max_value = self
+            .series
+            .iter()
+            .flat_map(|series| series.values.iter().copied())
+            .max()
+            .unwrap_or_default()
+            .max(1) as f64;
+        let steps = self.points.len().saturating_sub(1).max(1) as f64;
+
+        let x_of = |i: usize| LEFT + plot_width * i as f64 / steps;
+        let y_of = |value: i32| TOP + plot_height * (1.0 - value as f64 / max_value);
+
+        let mut svg = format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="12"><rect width="100%" height="100%" fill="#18181b"/>"##
+        );
+
+        for tick in 0..=TICKS {
+            let value = (max_value * tick as f64 / TICKS as f64).round() as i32;
+            let y = y_of(value);
+            let _ = write!(
+                svg,
+                r##"<line x1="{LEFT}" x2="{}" y1="{y}" y2="{y}" stroke="#3f3f46"/><text x="{}" y="{}" fill="#a1a1aa" text-anchor="end">{value}</text>"##,
+                LEFT + plot_width,
+                LEFT - 8.0,
+                y + 4.0,
+            );
+        }
+
+        for (i, x) in self.points.iter().enumerate() {
+            let _ = write!(
+                svg,
+                r##"<text x="{}" y="{}" fill="#a1a1aa" text-anchor="middle">{x}</text>"##,
+                x_of(i),
+                TOP + plot_height + 18.0,
+            );
+        }
+
+        let _ = write!(
+            svg,
+            r##"<text x="{}" y="{}" fill="#d4d4d8" text-anchor="middle">{}</text>"##,
+            LEFT + plot_width / 2.0,
+            height - 4.0,
+            xml_escape(&self.axis.label()),
+        );
+
+        for (n, series) in self.series.iter().enumerate() {
+            let color = PALETTE[n % PALETTE.len()];
+            let points = series
+                .values
+                .iter()
+                .enumerate()
+                .map(|(i, &value)| format!("{},{}", x_of(i), y_of(value)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let legend_y = TOP + 18.0 * n as f64;
+            let _ = write!(
+                svg,
+                r##"<polyline points="{points}" fill="none" stroke="{color}" stroke-width="2"/><rect x="{}" y="{}" width="10" height="10" fill="{color}"/><text x="{}" y="{}" fill="#e4e4e7">{}</text>"##,
+                width - RIGHT + 16.0,
+                legend_y,
+                width - RIGHT + 32.0,
+                legend_y + 9.0,
+                xml_escape(&series.label),
+            );
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_escapes_labels() {
+        let result = SweepResult {
+            axis: SweepAxis::Level,
+            champion_id: ChampionId::Veigar,
+            enemy_id: ChampionId::Nasus,
+            points: vec![1, 2],
+            series: vec![SweepSeries {
+                label: String::from("Q <Max> & \"Bonus\" 'Onhit'"),
+                values: vec![10, 20],
+            }],
+        };
+        let svg = result.to_svg();
+        assert!(svg.contains("Q &lt;Max&gt; &amp; &quot;Bonus&quot; &apos;Onhit&apos;"));
+        assert!(!svg.contains("<Max>"));
+        assert_eq!(xml_escape("Level (auto ranks)"), "Level (auto ranks)");
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    calculator::{AbilityLevels, Player, PlayerData},
+    model::{SimpleStats, Stats, Team},
+};
+use std::rc::Rc;
+
+impl From<&PlayerData<Stats>> for PlayerData<SimpleStats> {
+    fn from(value: &PlayerData<Stats>) -> Self {
+        PlayerData {
+            stats: SimpleStats {
+                armor: value.stats.armor,
+                health: value.stats.health,
+                magic_resist: value.stats.magic_resist,
+            },
+            items: value.items.clone(),
+            item_exceptions: value.item_exceptions.clone(),
+            stacks: value.stacks,
+            level: value.level,
+            infer_stats: value.infer_stats,
+            is_mega_gnar: value.is_mega_gnar,
+            champion_id: value.champion_id,
+        }
+    }
+}
+
+/// Promotes some player to active player. Since [`SimpleStats`] does not hold all
+/// the stats of [`Stats`], the missing ones are inferred, and the abilities are
+/// leveled up following [`AbilityLevels::at_level`]
+impl From<&PlayerData<SimpleStats>> for Player {
+    fn from(value: &PlayerData<SimpleStats>) -> Self {
+        Player {
+            runes: Vec::new(),
+            rune_exceptions: Vec::new(),
+            abilities: AbilityLevels::at_level(value.level),
+            data: PlayerData {
+                stats: Stats {
+                    armor: value.stats.armor,
+                    health: value.stats.health,
+                    current_health: value.stats.health,
+                    magic_resist: value.stats.magic_resist,
+                    ..Default::default()
+                },
+                items: value.items.clone(),
+                item_exceptions: value.item_exceptions.clone(),
+                stacks: value.stacks,
+                level: value.level,
+                infer_stats: true,
+                is_mega_gnar: value.is_mega_gnar,
+                champion_id: value.champion_id,
+            },
+        }
+    }
+}
+
+/// Restores the fields of a previous active player that [`PlayerData<SimpleStats>`]
+/// can not hold, such as its runes and ability ranks, when it is promoted again.
+/// Ability ranks are only restored if its level did not change in the meantime
+fn restore(promoted: &mut Player, benched: &Player) {
+    promoted.runes = benched.runes.clone();
+    promoted.rune_exceptions = benched.rune_exceptions.clone();
+    if promoted.data.level == benched.data.level {
+        promoted.abilities = benched.abilities;
+    }
+    promoted.data.infer_stats = benched.data.infer_stats;
+    promoted.data.stats = Stats {
+        armor: promoted.data.stats.armor,
+        health: promoted.data.stats.health,
+        magic_resist: promoted.data.stats.magic_resist,
+        ..benched.data.stats
+    };
+}
+
+/// Result of selecting a new active player, with the teams rearranged around it
+pub struct Lineup {
+    pub player: Player,
+    pub allies: Vec<PlayerData<SimpleStats>>,
+    pub enemies: Vec<PlayerData<SimpleStats>>,
+    pub team: Team,
+    /// Every player that was demoted from active player, see [`Lineup::promote`]
+    pub bench: Vec<Player>,
+}
+
+impl Lineup {
+    /// Makes the player at `index` of team `target` the active player. Damages are
+    /// always calculated against the team opposite to the active player, so selecting
+    /// a player from the enemy team swaps both lists. The previous active player keeps
+    /// its position as the first player of its team, and is kept in the `bench` so
+    /// that none of its fields are lost if it is promoted again. Players are found
+    /// in the bench by their champion, since it is unique within a game
+    pub fn promote(
+        player: &Player,
+        allies: &[Rc<PlayerData<SimpleStats>>],
+        enemies: &[Rc<PlayerData<SimpleStats>>],
+        team: Team,
+        target: Team,
+        index: usize,
+        bench: &[Player],
+    ) -> Self {
+        let demoted = PlayerData::<SimpleStats>::from(&player.data);
+        let to_vec = |slice: &[Rc<PlayerData<SimpleStats>>]| {
+            slice.iter().map(|p| (**p).clone()).collect::<Vec<_>>()
+        };
+
+        let (mut same_side, other_side) = match target == team {
+            true => (to_vec(allies), to_vec(enemies)),
+            false => (to_vec(enemies), to_vec(allies)),
+        };
+        let promoted = same_side.remove(index);
+
+        let (allies, enemies) = match target == team {
+            true => {
+                same_side.insert(0, demoted);
+                (same_side, other_side)
+            }
+            false => {
+                let mut other_side = other_side;
+                other_side.insert(0, demoted);
+                (same_side, other_side)
+            }
+        };
+
+        let mut new_player = Player::from(&promoted);
+        if let Some(benched) = bench
+            .iter()
+            .find(|benched| benched.data.champion_id == promoted.champion_id)
+        {
+            restore(&mut new_player, benched);
+        }
+        let bench = bench
+            .iter()
+            .filter(|benched| {
+                benched.data.champion_id != promoted.champion_id
+                    && benched.data.champion_id != player.data.champion_id
+            })
+            .cloned()
+            .chain(std::iter::once(player.clone()))
+            .collect();
+
+        Lineup {
+            player: new_player,
+            allies,
+            enemies,
+            team: target,
+            bench,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::runes::all_runes;
+    use tutorlolv2_gen::ChampionId;
+
+    fn enemy(champion_id: ChampionId) -> Rc<PlayerData<SimpleStats>> {
+        Rc::new(PlayerData {
+            champion_id,
+            level: 9,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn keeps_runes_and_ranks_when_swapping_back() {
+        let mut player = Player::default();
+        player.data.champion_id = ChampionId::Ahri;
+        player.data.level = 9;
+        player.runes = all_runes().take(2).collect();
+        player.abilities = AbilityLevels {
+            q: 5,
+            w: 1,
+            e: 3,
+            r: 1,
+        };
+        player.data.stats.ability_power = 120;
+        let enemies = [enemy(ChampionId::Zed)];
+
+        let first = Lineup::promote(&player, &[], &enemies, Team::Blue, Team::Red, 0, &[]);
+        assert_eq!(first.player.data.champion_id, ChampionId::Zed);
+        assert_eq!(first.enemies.len(), 1);
+
+        let enemies = first.enemies.into_iter().map(Rc::new).collect::<Vec<_>>();
+        let allies = first.allies.into_iter().map(Rc::new).collect::<Vec<_>>();
+        let second = Lineup::promote(
+            &first.player,
+            &allies,
+            &enemies,
+            first.team,
+            Team::Blue,
+            0,
+            &first.bench,
+        );
+        assert_eq!(second.player, player);
+        assert_eq!(second.bench.len(), 1);
+        assert_eq!(second.bench[0].data.champion_id, ChampionId::Zed);
+    }
+}
@@ -0,0 +1,378 @@
+//! Tooltip that shows the formula of an [`Image`](crate::components::image::Image).
+//! It opens while hovering an image with `Shift` held, and stays open while the
+//! pointer is over the image or the tooltip, so that long formulas can be
+//! scrolled. It also opens when a focusable image receives keyboard focus, and is
+//! toggled by tapping an image on touch screens
+use crate::{
+    components::inspector::{EvaluatedFormula, PinAction, PinnedFormulas},
+    utils::{ImageType, cache::notify_when_ready},
+};
+use gloo_timers::callback::Timeout;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{JsCast, closure::Closure};
+use web_sys::{Element, EventTarget, Node};
+use yew::prelude::*;
+
+/// Maximum size of the tooltip in pixels, matching `max-w-md` and `max-h-96`
+const TOOLTIP_WIDTH: f64 = 448.0;
+const TOOLTIP_HEIGHT: f64 = 384.0;
+
+/// Distance between the tooltip and the image it belongs to, in pixels
+const GAP: f64 = 4.0;
+
+/// Time in milliseconds the tooltip stays open after the pointer leaves it or
+/// its image, so that the pointer can cross the [`GAP`] between both
+const HIDE_DELAY: u32 = 200;
+
+/// Image whose formula is shown, along with the element that displays it
+#[derive(Clone, PartialEq)]
+pub struct DocsAnchor {
+    pub image: ImageType,
+    pub element: Element,
+}
+
+#[derive(Default)]
+struct DocsState {
+    shown: Option<DocsAnchor>,
+    hovered: Option<DocsAnchor>,
+    shift: bool,
+    /// Whether the tooltip shows the evaluated formula instead of the generic one
+    evaluated: bool,
+    tooltip: NodeRef,
+    /// Pending hide, cancelled when dropped
+    hide_timeout: Option<Timeout>,
+}
+
+impl DocsState {
+    /// Returns true if `target` is inside the tooltip or the shown image
+    fn contains(&self, target: Option<&EventTarget>) -> bool {
+        let Some(node) = target.and_then(|target| target.dyn_ref::<Node>()) else {
+            return false;
+        };
+        let in_tooltip = self
+            .tooltip
+            .cast::<Node>()
+            .is_some_and(|tooltip| tooltip.contains(Some(node)));
+        let in_anchor = self
+            .shown
+            .as_ref()
+            .is_some_and(|anchor| anchor.element.contains(Some(node)));
+        in_tooltip || in_anchor
+    }
+}
+
+/// Context shared with every image. Changes to it only render the
+/// [`DocsProvider`], so images are not rendered again when the tooltip moves
+#[derive(Clone)]
+pub struct HoverDocs {
+    state: Rc<RefCell<DocsState>>,
+    rerender: UseForceUpdateHandle,
+}
+
+impl PartialEq for HoverDocs {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl HoverDocs {
+    /// Applies `f` to the state, rendering the tooltip again if it returns true
+    fn update(&self, f: impl FnOnce(&mut DocsState) -> bool) {
+        let changed = f(&mut self.state.borrow_mut());
+        if changed {
+            self.rerender.force_update();
+        }
+    }
+
+    pub fn show(&self, anchor: DocsAnchor) {
+        self.update(|state| {
+            state.hide_timeout = None;
+            state.shown.replace(anchor.clone()) != Some(anchor)
+        });
+    }
+
+    pub fn hide(&self) {
+        self.update(|state| state.shown.take().is_some());
+    }
+
+    /// Shows `anchor` if it is not shown yet, and hides it otherwise
+    pub fn toggle(&self, anchor: DocsAnchor) {
+        self.update(|state| {
+            state.shown = match state.shown.as_ref() == Some(&anchor) {
+                true => None,
+                false => Some(anchor),
+            };
+            true
+        });
+    }
+
+    /// Hides the tooltip after [`HIDE_DELAY`], unless the pointer enters the
+    /// tooltip or its image before that
+    fn hide_later(&self) {
+        let docs = self.clone();
+        let timeout = Timeout::new(HIDE_DELAY, move || docs.hide());
+        self.state.borrow_mut().hide_timeout = Some(timeout);
+    }
+
+    fn cancel_hide(&self) {
+        self.state.borrow_mut().hide_timeout = None;
+    }
+
+    /// Called when the pointer enters an image, which is shown if `Shift` is held
+    pub fn enter(&self, anchor: DocsAnchor) {
+        self.update(|state| {
+            if state.shown.as_ref() == Some(&anchor) {
+                state.hide_timeout = None;
+            }
+            state.hovered = Some(anchor.clone());
+            match state.shift && state.shown.as_ref() != Some(&anchor) {
+                true => {
+                    state.hide_timeout = None;
+                    state.shown = Some(anchor);
+                    true
+                }
+                false => false,
+            }
+        });
+    }
+
+    /// Called when the pointer or focus leaves `element` towards `to`. The
+    /// tooltip is kept open if `to` is inside of it, and is hidden after
+    /// [`HIDE_DELAY`] otherwise
+    pub fn leave(&self, element: &Element, to: Option<EventTarget>) {
+        let hide = {
+            let mut state = self.state.borrow_mut();
+            if state
+                .hovered
+                .as_ref()
+                .is_some_and(|anchor| anchor.element == *element)
+            {
+                state.hovered = None;
+            }
+            let is_shown = state
+                .shown
+                .as_ref()
+                .is_some_and(|anchor| anchor.element == *element);
+            is_shown && !state.contains(to.as_ref())
+        };
+        if hide {
+            self.hide_later();
+        }
+    }
+}
+
+/// Position of the tooltip, below `element` if it fits in the viewport and
+/// above otherwise, and moved to the left if it would overflow horizontally
+fn position(element: &Element) -> String {
+    let rect = element.get_bounding_client_rect();
+    let viewport = |value: Option<f64>| value.unwrap_or(f64::INFINITY);
+    let window = web_sys::window();
+    let width = viewport(window.as_ref().and_then(|w| w.inner_width().ok()?.as_f64()));
+    let height = viewport(
+        window
+            .as_ref()
+            .and_then(|w| w.inner_height().ok()?.as_f64()),
+    );
+
+    let left = rect.left().min(width - TOOLTIP_WIDTH).max(0.0);
+    let vertical = match rect.bottom() + GAP + TOOLTIP_HEIGHT <= height {
+        true => format!("top: {}px", rect.bottom() + GAP),
+        false => format!("bottom: {}px", height - rect.top() + GAP),
+    };
+    format!("left: {left}px; {vertical}")
+}
+
+#[derive(PartialEq, Properties)]
+pub struct DocsProviderProps {
+    pub children: Html,
+}
+
+/// Provides [`HoverDocs`] to every image inside of it, and renders the tooltip
+/// in a portal so that it is not clipped by scrolling containers
+#[component]
+pub fn DocsProvider(props: &DocsProviderProps) -> Html {
+    let state = use_mut_ref(DocsState::default);
+    let rerender = use_force_update();
+    let pin = use_context::<UseReducerDispatcher<PinnedFormulas>>();
+    let bindings =
+        use_context::<UseReducerHandle<PinnedFormulas>>().and_then(|pinned| pinned.bindings());
+    let docs = HoverDocs { state, rerender };
+
+    {
+        let docs = docs.clone();
+        use_effect_with((), move |_| {
+            let on_keydown = {
+                let docs = docs.clone();
+                Closure::<dyn Fn(KeyboardEvent)>::new(move |e: KeyboardEvent| {
+                    match e.key().as_str() {
+                        "Shift" => docs.update(|state| {
+                            state.shift = true;
+                            if state.shown.is_some() {
+                                return false;
+                            }
+                            state.shown = state.hovered.clone();
+                            state.shown.is_some()
+                        }),
+                        "Escape" => docs.hide(),
+                        _ => {}
+                    }
+                })
+            };
+            let on_keyup = {
+                let docs = docs.clone();
+                Closure::<dyn Fn(KeyboardEvent)>::new(move |e: KeyboardEvent| {
+                    if e.key() == "Shift" {
+                        docs.update(|state| {
+                            state.shift = false;
+                            false
+                        });
+                    }
+                })
+            };
+            // Closes the tooltip when tapping or clicking anywhere else
+            let on_pointerdown = {
+                let docs = docs.clone();
+                Closure::<dyn Fn(PointerEvent)>::new(move |e: PointerEvent| {
+                    let outside = !docs.state.borrow().contains(e.target().as_ref());
+                    if outside {
+                        docs.hide();
+                    }
+                })
+            };
+            // Scrolling the tooltip itself keeps it open
+            let on_scroll = {
+                let docs = docs.clone();
+                Closure::<dyn Fn(Event)>::new(move |e: Event| {
+                    let outside = !docs.state.borrow().contains(e.target().as_ref());
+                    if outside {
+                        docs.hide();
+                    }
+                })
+            };
+
+            // Scroll events do not bubble, so the scroll listener is registered
+            // for the capture phase to also receive those of scrolling containers
+            let window = web_sys::window();
+            let listeners: [(&str, js_sys::Function, bool); 4] = [
+                (
+                    "keydown",
+                    on_keydown.as_ref().clone().unchecked_into(),
+                    false,
+                ),
+                ("keyup", on_keyup.as_ref().clone().unchecked_into(), false),
+                (
+                    "pointerdown",
+                    on_pointerdown.as_ref().clone().unchecked_into(),
+                    false,
+                ),
+                ("scroll", on_scroll.as_ref().clone().unchecked_into(), true),
+            ];
+            if let Some(window) = &window {
+                for (kind, listener, capture) in &listeners {
+                    let _ =
+                        window.add_event_listener_with_callback_and_bool(kind, listener, *capture);
+                }
+            }
+            move || {
+                if let Some(window) = &window {
+                    for (kind, listener, capture) in &listeners {
+                        let _ = window
+                            .remove_event_listener_with_callback_and_bool(kind, listener, *capture);
+                    }
+                }
+                drop((on_keydown, on_keyup, on_pointerdown, on_scroll));
+            }
+        });
+    }
+
+    let tooltip = {
+        let state = docs.state.borrow();
+        let body = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.body());
+        match (&state.shown, body) {
+            (Some(anchor), Some(body)) => {
+                let onmouseenter = {
+                    let docs = docs.clone();
+                    Callback::from(move |_: MouseEvent| docs.cancel_hide())
+                };
+                let onmouseleave = {
+                    let docs = docs.clone();
+                    Callback::from(move |e: MouseEvent| {
+                        let to = e.related_target();
+                        let outside = !docs.state.borrow().contains(to.as_ref());
+                        if outside {
+                            docs.hide_later();
+                        }
+                    })
+                };
+                let button = classes!("px-2", "text-sm", "bg-std-800", "hover:bg-std-700");
+                let pin_button = pin.map(|pin| {
+                    let image = anchor.image;
+                    let onclick = Callback::from(move |_: MouseEvent| {
+                        pin.dispatch(PinAction::Pin(image));
+                    });
+                    html! { <button class={button.clone()} {onclick}>{"Pin"}</button> }
+                });
+                let on_evaluate = {
+                    let docs = docs.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        docs.update(|state| {
+                            state.evaluated = !state.evaluated;
+                            true
+                        })
+                    })
+                };
+                let docs_html = anchor.image.docs().unwrap_or_else(|| {
+                    let docs = docs.clone();
+                    notify_when_ready(Callback::from(move |_| docs.rerender.force_update()));
+                    Rc::default()
+                });
+                let content = match (state.evaluated, &bindings) {
+                    (true, Some(bindings)) => html! {
+                        <EvaluatedFormula html={docs_html} bindings={bindings.clone()} />
+                    },
+                    _ => Html::from_html_unchecked(docs_html.into()),
+                };
+                create_portal(
+                    html! {
+                        <div
+                            ref={state.tooltip.clone()}
+                            role="tooltip"
+                            style={position(&anchor.element)}
+                            class={classes!(
+                                "flex", "flex-col", "fixed", "max-w-md", "max-h-96", "overflow-auto",
+                                "p-2", "leading-6", "text-base", "z-50", "hover-docs", "border",
+                                "border-std-800", "bg-std-900",
+                            )}
+                            {onmouseenter}
+                            {onmouseleave}
+                        >
+                            <div class={classes!("flex", "gap-1", "self-end")}>
+                                <button class={button} disabled={bindings.is_none()} onclick={on_evaluate}>
+                                    {match state.evaluated {
+                                        true => "Formula",
+                                        false => "Evaluate",
+                                    }}
+                                </button>
+                                {pin_button}
+                            </div>
+                            <code class={classes!("text-[#D4D4D4]", "font-normal", "text-left", "text-wrap")}>
+                                {content}
+                            </code>
+                        </div>
+                    },
+                    body.into(),
+                )
+            }
+            _ => html! {},
+        }
+    };
+
+    html! {
+        <ContextProvider<HoverDocs> context={docs}>
+            {props.children.clone()}
+            {tooltip}
+        </ContextProvider<HoverDocs>>
+    }
+}
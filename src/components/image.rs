@@ -1,4 +1,15 @@
-use crate::utils::ImageType;
+use crate::{
+    components::{
+        docs::{DocsAnchor, HoverDocs},
+        inspector::{PinAction, PinnedFormulas},
+    },
+    utils::{
+        ImageType,
+        images::{ImageMode, sheet_available},
+    },
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlInputElement};
 use yew::prelude::*;
 
 #[derive(PartialEq, Properties)]
@@ -6,26 +17,154 @@ pub struct ImageProps {
     #[prop_or(classes!("w-8", "h-8"))]
     pub class: Classes,
     pub src: ImageType,
+    /// Whether the image can be focused to show its formula. Images inside
+    /// buttons or other interactive elements are reached through them instead
+    #[prop_or_default]
+    pub focusable: bool,
 }
 
 #[component]
 pub fn Image(props: &ImageProps) -> Html {
-    let ImageProps { class, src } = props;
-    let (main_offset, exc_offset) = src.offset();
+    let ImageProps {
+        class,
+        src,
+        focusable,
+    } = props;
+    let docs = use_context::<HoverDocs>();
+    let pin = use_context::<UseReducerDispatcher<PinnedFormulas>>();
+    let node = use_node_ref();
     let header = src.header();
-    let src = src.url();
+    let failed = use_state(|| false);
+    let rerender = use_force_update();
+
+    {
+        let failed = failed.clone();
+        use_effect_with(*src, move |_| failed.set(false));
+    }
+
+    // Every handler does nothing if the image is not inside of a `DocsProvider`
+    let handler = |f: fn(&HoverDocs, DocsAnchor, Event)| {
+        let docs = docs.clone();
+        let node = node.clone();
+        let image = *src;
+        Callback::from(move |e: Event| {
+            if let Some(docs) = &docs
+                && let Some(element) = node.cast::<Element>()
+            {
+                f(docs, DocsAnchor { image, element }, e);
+            }
+        })
+    };
+    let leave = |docs: &HoverDocs, anchor: DocsAnchor, e: Event| {
+        let to = match e.dyn_ref::<FocusEvent>() {
+            Some(e) => e.related_target(),
+            None => e.unchecked_ref::<MouseEvent>().related_target(),
+        };
+        docs.leave(&anchor.element, to);
+    };
+
+    let onmouseenter = handler(|docs, anchor, _| docs.enter(anchor)).reform(Event::from);
+    let onmouseleave = handler(leave).reform(Event::from);
+    let onblur = handler(leave).reform(Event::from);
+    // Focus from clicks is ignored, otherwise every click would show the formula
+    let onfocus = handler(|docs, anchor, _| {
+        if anchor.element.matches(":focus-visible").unwrap_or(false) {
+            docs.show(anchor);
+        }
+    })
+    .reform(Event::from);
+    let onpointerup = handler(|docs, anchor, e| {
+        if e.unchecked_ref::<PointerEvent>().pointer_type() == "touch" {
+            docs.toggle(anchor);
+        }
+    })
+    .reform(Event::from);
+
+    // Pinning takes precedence over the click handlers of the parent elements
+    let onclick = {
+        let image = *src;
+        Callback::from(move |e: MouseEvent| {
+            if e.alt_key()
+                && let Some(pin) = &pin
+            {
+                e.prevent_default();
+                e.stop_propagation();
+                pin.dispatch(PinAction::Pin(image));
+            }
+        })
+    };
 
     let mut classes = classes!("flex", "items-center", "justify-center", "relative", "cell");
     classes.push(class);
 
+    let alt = src.name();
+    // Icons fall back to their own files if the sheet fails to load
+    let atlas_cell = match ImageMode::current() {
+        ImageMode::Atlas => src.atlas_cell().filter(|cell| {
+            let rerender = rerender.clone();
+            sheet_available(
+                cell.category,
+                &Callback::from(move |_| rerender.force_update()),
+            )
+        }),
+        ImageMode::Individual => None,
+    };
+    let icon = match (*failed, atlas_cell) {
+        (true, _) => html! {
+            <span role="img" aria-label={alt} class={classes!("text-xs", "text-std-400")}>
+                {src.initials()}
+            </span>
+        },
+        (false, Some(cell)) => html! {
+            <div role="img" aria-label={alt} class={classes!("w-full", "h-full")} style={cell.style()} />
+        },
+        (false, None) => {
+            let onerror = {
+                let failed = failed.clone();
+                Callback::from(move |_: Event| failed.set(true))
+            };
+            html! { <img loading={"lazy"} src={src.url()} {alt} {onerror} /> }
+        }
+    };
+
     html! {
         <div
-            data-offset-main={main_offset}
-            data-offset-exc={exc_offset}
+            ref={node}
             class={classes}
+            tabindex={focusable.then_some("0")}
+            {onmouseenter}
+            {onmouseleave}
+            {onfocus}
+            {onblur}
+            {onpointerup}
+            {onclick}
         >
-            <img loading={"lazy"} {src} alt={""} />
+            {icon}
             {header}
         </div>
     }
 }
+
+/// Checkbox that switches between loading icons one by one and cutting them
+/// from the sprite sheets, which reloads the page
+#[component]
+pub fn ImageModeToggle() -> Html {
+    let onchange = Callback::from(|e: Event| {
+        let checked = e.target_unchecked_into::<HtmlInputElement>().checked();
+        match checked {
+            true => ImageMode::Atlas.save(),
+            false => ImageMode::Individual.save(),
+        }
+    });
+
+    html! {
+        <label class={classes!("flex", "gap-2", "items-center", "text-sm", "text-std-400")}>
+            <input
+                type="checkbox"
+                checked={ImageMode::current() == ImageMode::Atlas}
+                {onchange}
+            />
+            {"Load icons from sprite sheets"}
+        </label>
+    }
+}
@@ -0,0 +1,323 @@
+use crate::{
+    calculator::{
+        AbilityLevels, Game,
+        evaluate::{Binding, bindings, evaluate, format_number},
+        reducer::EnemyId,
+    },
+    components::{image::Image, reorder::use_reorder},
+    utils::{ImageType, cache::notify_when_ready},
+};
+use std::rc::Rc;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+/// Formulas pinned to the inspector, in display order. They are kept outside
+/// of the calculator state, so recalculating the game does not change them.
+/// The last calculated game is kept as well, so formulas can be evaluated
+#[derive(Clone, Default, PartialEq)]
+pub struct PinnedFormulas {
+    pub images: Vec<ImageType>,
+    pub game: Option<Rc<Game>>,
+    pub ranks: AbilityLevels,
+    /// Identifiers of the enemies of `game`, in the same order
+    pub enemy_ids: Vec<EnemyId>,
+    /// Enemy whose stats are used to evaluate formulas, which is the first
+    /// one if it is not set or was removed
+    pub enemy: Option<EnemyId>,
+}
+
+impl PinnedFormulas {
+    /// Index of [`PinnedFormulas::enemy`] in the enemies of the game
+    pub fn enemy_index(&self) -> usize {
+        self.enemy
+            .and_then(|id| self.enemy_ids.iter().position(|other| *other == id))
+            .unwrap_or_default()
+    }
+
+    /// Values used to evaluate formulas, or [`None`] if no game was calculated yet
+    pub fn bindings(&self) -> Option<Rc<[Binding]>> {
+        let game = self.game.as_ref()?;
+        Some(bindings(game, self.ranks, self.enemy_index()).into())
+    }
+}
+
+pub enum PinAction {
+    /// Pins a formula, unless it is pinned already
+    Pin(ImageType),
+    Unpin(usize),
+    /// Moves the card at the first index to the second one
+    Move(usize, usize),
+    Clear,
+    /// Sets the game that formulas are evaluated against, the ability ranks of
+    /// the active player and the identifiers of the enemies sent with the
+    /// request, which are not part of the game
+    Game(Rc<Game>, AbilityLevels, Vec<EnemyId>),
+    Enemy(EnemyId),
+}
+
+impl Reducible for PinnedFormulas {
+    type Action = PinAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut new = (*self).clone();
+        match action {
+            PinAction::Pin(image) => {
+                if new.images.contains(&image) {
+                    return self;
+                }
+                new.images.push(image);
+            }
+            PinAction::Unpin(index) => {
+                if index < new.images.len() {
+                    new.images.remove(index);
+                }
+            }
+            PinAction::Move(from, to) => {
+                if from < new.images.len() && to < new.images.len() {
+                    let image = new.images.remove(from);
+                    new.images.insert(to, image);
+                }
+            }
+            PinAction::Clear => new.images.clear(),
+            PinAction::Game(game, ranks, enemy_ids) => {
+                new.game = Some(game);
+                new.ranks = ranks;
+                new.enemy_ids = enemy_ids;
+            }
+            PinAction::Enemy(id) => new.enemy = Some(id),
+        }
+        new.into()
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct InspectorProviderProps {
+    pub children: Html,
+}
+
+/// Provides the [`PinnedFormulas`] to the [`InspectorPanel`], and a dispatcher
+/// to pin formulas to everything else. Since the dispatcher never changes,
+/// images are not rendered again when a formula is pinned
+#[component]
+pub fn InspectorProvider(props: &InspectorProviderProps) -> Html {
+    let pinned = use_reducer(PinnedFormulas::default);
+
+    html! {
+        <ContextProvider<UseReducerHandle<PinnedFormulas>> context={pinned.clone()}>
+            <ContextProvider<UseReducerDispatcher<PinnedFormulas>> context={pinned.dispatcher()}>
+                {props.children.clone()}
+            </ContextProvider<UseReducerDispatcher<PinnedFormulas>>>
+        </ContextProvider<UseReducerHandle<PinnedFormulas>>>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct EvaluatedFormulaProps {
+    pub html: Rc<str>,
+    pub bindings: Rc<[Binding]>,
+}
+
+/// Formula with the current values substituted, showing the values that were
+/// used and each step of the evaluation of every line
+#[component]
+pub fn EvaluatedFormula(props: &EvaluatedFormulaProps) -> Html {
+    let EvaluatedFormulaProps { html, bindings } = props;
+    let evaluation = use_memo((html.clone(), bindings.clone()), |(html, bindings)| {
+        evaluate(html, bindings)
+    });
+
+    let used = evaluation
+        .used
+        .iter()
+        .map(|binding| {
+            html! {
+                <span class={classes!("text-sm", "text-std-300")}>
+                    {format!("{} = {}", binding.name, format_number(binding.value))}
+                </span>
+            }
+        })
+        .collect::<Html>();
+
+    let lines = evaluation
+        .lines
+        .iter()
+        .map(|line| {
+            let steps = line
+                .steps
+                .iter()
+                .enumerate()
+                .map(|(i, step)| {
+                    let prefix = match i {
+                        0 => "→ ",
+                        _ => "= ",
+                    };
+                    html! { <span class={classes!("pl-4", "text-[#B3CDA8]")}>{prefix}{step}</span> }
+                })
+                .collect::<Html>();
+            html! {
+                <div class={classes!("flex", "flex-col")}>
+                    <span class={classes!("text-std-400")}>{&line.original}</span>
+                    {steps}
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-1", "font-mono", "text-sm")}>
+            <div class={classes!("flex", "gap-x-3", "flex-wrap")}>{used}</div>
+            {lines}
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct FormulaCardProps {
+    pub image: ImageType,
+    pub onclose: Callback<MouseEvent>,
+    #[prop_or_default]
+    pub bindings: Option<Rc<[Binding]>>,
+}
+
+#[component]
+pub fn FormulaCard(props: &FormulaCardProps) -> Html {
+    let FormulaCardProps {
+        image,
+        onclose,
+        bindings,
+    } = props;
+    let evaluated = use_state(|| false);
+    let rerender = use_force_update();
+    // Formulas are missing while the worker decompresses the cache
+    let docs = image.docs().unwrap_or_else(|| {
+        notify_when_ready(Callback::from(move |_| rerender.force_update()));
+        Rc::default()
+    });
+
+    let on_toggle = {
+        let evaluated = evaluated.clone();
+        Callback::from(move |_: MouseEvent| evaluated.set(!*evaluated))
+    };
+
+    let content = match (*evaluated, bindings) {
+        (true, Some(bindings)) => html! {
+            <EvaluatedFormula html={docs} bindings={bindings.clone()} />
+        },
+        _ => Html::from_html_unchecked(docs.into()),
+    };
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-2", "w-96", "max-h-96", "p-2", "border", "border-std-800", "bg-std-900")}>
+            <div class={classes!("flex", "gap-2", "items-center")}>
+                <Image src={*image} focusable={true} />
+                <span class={classes!("flex-1")}>{image.name()}</span>
+                <button
+                    class={classes!("px-2", "text-sm", "bg-std-800", "hover:bg-std-700")}
+                    disabled={bindings.is_none()}
+                    onclick={on_toggle}
+                >
+                    {match *evaluated {
+                        true => "Formula",
+                        false => "Evaluate",
+                    }}
+                </button>
+                <button class={classes!("px-2", "hover:bg-std-700")} onclick={onclose}>{"×"}</button>
+            </div>
+            <code class={classes!("hover-docs", "overflow-auto", "leading-6", "text-[#D4D4D4]", "text-wrap")}>
+                {content}
+            </code>
+        </div>
+    }
+}
+
+/// Cards of every pinned formula, side by side. Formulas are pinned by
+/// clicking an image with `Alt` held, or from the hover docs tooltip, and
+/// cards can be dragged to rearrange them
+#[component]
+pub fn InspectorPanel() -> Html {
+    let pinned = use_context::<UseReducerHandle<PinnedFormulas>>();
+    let onmove = {
+        let pinned = pinned.clone();
+        Callback::from(move |(from, to): (usize, usize)| {
+            if let Some(pinned) = &pinned {
+                pinned.dispatch(PinAction::Move(from, to));
+            }
+        })
+    };
+    let reorder = use_reorder(onmove);
+
+    let Some(pinned) = pinned.filter(|pinned| !pinned.images.is_empty()) else {
+        return html! {};
+    };
+
+    let on_clear = {
+        let pinned = pinned.clone();
+        Callback::from(move |_: MouseEvent| pinned.dispatch(PinAction::Clear))
+    };
+
+    let on_enemy = {
+        let pinned = pinned.clone();
+        Callback::from(move |e: Event| {
+            let index = e
+                .target_unchecked_into::<HtmlSelectElement>()
+                .selected_index();
+            if let Some(id) = pinned.enemy_ids.get(index.max(0) as usize) {
+                pinned.dispatch(PinAction::Enemy(*id));
+            }
+        })
+    };
+
+    let bindings = pinned.bindings();
+    let enemy_index = pinned.enemy_index();
+    let enemies = pinned
+        .game
+        .iter()
+        .flat_map(|game| game.enemies.iter())
+        .enumerate()
+        .map(|(i, enemy)| {
+            html! {
+                <option selected={i == enemy_index}>{enemy.champion_id.name()}</option>
+            }
+        })
+        .collect::<Html>();
+
+    let cards = pinned
+        .images
+        .iter()
+        .enumerate()
+        .map(|(i, image)| {
+            let onclose = {
+                let pinned = pinned.clone();
+                Callback::from(move |_: MouseEvent| pinned.dispatch(PinAction::Unpin(i)))
+            };
+            let handlers = reorder.handlers(i);
+            // Cards hold their own state, which must follow them when moved
+            html! {
+                <div
+                    key={format!("{image:?}")}
+                    draggable="true"
+                    ondragstart={handlers.ondragstart}
+                    ondragover={handlers.ondragover}
+                    ondrop={handlers.ondrop}
+                    ondragend={handlers.ondragend}
+                >
+                    <FormulaCard image={*image} {onclose} bindings={bindings.clone()} />
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-2")}>
+            <div class={classes!("flex", "gap-2", "items-center")}>
+                <span>{"Pinned formulas"}</span>
+                <span class={classes!("text-sm", "text-std-400")}>{"Evaluated against"}</span>
+                <select class={classes!("bg-std-900")} onchange={on_enemy}>{enemies}</select>
+                <button class={classes!("px-2", "py-1", "bg-std-800", "hover:bg-std-700")} onclick={on_clear}>
+                    {"Clear"}
+                </button>
+            </div>
+            <div class={classes!("flex", "gap-2", "flex-wrap", "items-start")}>{cards}</div>
+        </div>
+    }
+}
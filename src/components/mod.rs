@@ -1,2 +1,9 @@
+pub mod docs;
 pub mod image;
+pub mod inspector;
+pub mod offline;
+pub mod picker;
+pub mod range;
+pub mod reorder;
 pub mod tables;
+pub mod task;
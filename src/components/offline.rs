@@ -0,0 +1,60 @@
+use crate::utils::offline::{self, OfflineEvent};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// Checkbox that enables the offline mode, along with the progress of the
+/// service worker while it downloads every asset. If it was enabled before,
+/// the assets of the current version are requested again on startup
+#[component]
+pub fn OfflineToggle() -> Html {
+    let enabled = use_state(offline::is_enabled);
+    let status = use_state(|| None::<OfflineEvent>);
+
+    {
+        let status = status.clone();
+        use_effect_with((), move |_| {
+            offline::listen(Callback::from(move |event| status.set(Some(event))));
+            if offline::is_enabled() {
+                offline::enable();
+            }
+        });
+    }
+
+    if !offline::is_supported() {
+        return html! {};
+    }
+
+    let onchange = {
+        let enabled = enabled.clone();
+        let status = status.clone();
+        Callback::from(move |e: Event| {
+            let checked = e.target_unchecked_into::<HtmlInputElement>().checked();
+            match checked {
+                true => offline::enable(),
+                false => offline::disable(),
+            }
+            enabled.set(checked);
+            status.set(None);
+        })
+    };
+
+    let message = match (*enabled, &*status) {
+        (true, None) => Some(String::from("Downloading assets...")),
+        (true, Some(OfflineEvent::Progress { done, total })) => {
+            Some(format!("Downloading assets {done}/{total}"))
+        }
+        (true, Some(OfflineEvent::Ready { failed: 0 })) => Some(String::from("Ready")),
+        (true, Some(OfflineEvent::Ready { failed })) => {
+            Some(format!("Ready, {failed} assets failed to download"))
+        }
+        _ => None,
+    };
+
+    html! {
+        <label class={classes!("flex", "gap-2", "items-center", "text-sm", "text-std-400")}>
+            <input type="checkbox" checked={*enabled} {onchange} />
+            {"Offline mode"}
+            {message.map(|message| html! { <span class={classes!("text-std-300")}>{message}</span> })}
+        </label>
+    }
+}
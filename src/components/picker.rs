@@ -0,0 +1,275 @@
+//! Searchable dropdown over the variants of any [`EnumCast`] type
+use crate::{
+    components::image::Image,
+    data::{items, runes},
+    utils::{EnumCast, fuzzy::fuzzy_score, storage},
+};
+use std::rc::Rc;
+use tutorlolv2_gen::{ChampionId, ItemId, RuneId};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// Maximum number of recently picked entries remembered for each type
+const MAX_RECENTS: usize = 8;
+
+const RECENT_GROUP: &str = "Recent";
+
+/// Types that can be picked with a [`Picker`]
+pub trait Pickable: EnumCast + 'static {
+    /// Key of the local storage under which recent picks are saved
+    const RECENTS_KEY: &str;
+
+    /// Group under which this entry is listed while there is no search query.
+    /// The generated data does not classify champions, so they are not grouped
+    fn group(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+impl Pickable for ChampionId {
+    const RECENTS_KEY: &str = "recent_champions";
+}
+
+impl Pickable for ItemId {
+    const RECENTS_KEY: &str = "recent_items";
+
+    fn group(&self) -> Option<&'static str> {
+        match items::is_boots(*self) {
+            true => Some("Boots"),
+            false => items::unique_group(*self).map(|group| group.name()),
+        }
+    }
+}
+
+impl Pickable for RuneId {
+    const RECENTS_KEY: &str = "recent_runes";
+
+    fn group(&self) -> Option<&'static str> {
+        runes::rune_slot(*self).map(|slot| slot.tree.name())
+    }
+}
+
+fn load_recents<T: Pickable>(options: &[T]) -> Vec<T> {
+    storage::load(T::RECENTS_KEY)
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|name| options.iter().copied().find(|option| option.name() == name))
+        .collect()
+}
+
+fn save_recent<T: Pickable>(options: &[T], value: T) {
+    let mut recents = load_recents(options);
+    recents.retain(|recent| *recent != value);
+    recents.insert(0, value);
+    recents.truncate(MAX_RECENTS);
+    let names = recents.iter().map(|recent| recent.name());
+    storage::save(T::RECENTS_KEY, &names.collect::<Vec<_>>().join(","));
+}
+
+/// Entries listed for `query`, each with the group header displayed above it.
+/// Without a query, recent picks come first followed by every option grouped
+fn entries<T: Pickable>(options: &[T], query: &str) -> Vec<(Option<&'static str>, T)> {
+    if !query.trim().is_empty() {
+        let mut scored = options
+            .iter()
+            .filter_map(|option| Some((fuzzy_score(query, option.name())?, *option)))
+            .collect::<Vec<_>>();
+        scored.sort_by(|(a, x), (b, y)| b.cmp(a).then_with(|| x.name().cmp(y.name())));
+        return scored
+            .into_iter()
+            .map(|(_, option)| (None, option))
+            .collect();
+    }
+
+    let mut grouped = options.to_vec();
+    grouped.sort_by(|a, b| {
+        (a.group().is_none(), a.group(), a.name()).cmp(&(b.group().is_none(), b.group(), b.name()))
+    });
+    load_recents(options)
+        .into_iter()
+        .map(|option| (Some(RECENT_GROUP), option))
+        .chain(grouped.into_iter().map(|option| (option.group(), option)))
+        .collect()
+}
+
+#[derive(PartialEq, Properties)]
+pub struct PickerProps<T: Pickable> {
+    pub options: Rc<[T]>,
+    #[prop_or_default]
+    pub value: Option<T>,
+    pub onselect: Callback<T>,
+    #[prop_or("Select")]
+    pub placeholder: &'static str,
+}
+
+/// Dropdown with fuzzy search over `options`. While open, the arrow keys move
+/// the highlighted entry, `Enter` picks it and `Escape` closes the dropdown
+#[component]
+pub fn Picker<T: Pickable>(props: &PickerProps<T>) -> Html {
+    let PickerProps {
+        options,
+        value,
+        onselect,
+        placeholder,
+    } = props;
+
+    let open = use_state(|| false);
+    let query = use_state(String::new);
+    let highlighted = use_state(|| 0usize);
+    let input = use_node_ref();
+
+    {
+        let input = input.clone();
+        use_effect_with(*open, move |open| {
+            if *open && let Some(element) = input.cast::<HtmlInputElement>() {
+                let _ = element.focus();
+            }
+        });
+    }
+
+    let entries = Rc::new(entries(options, &query));
+
+    let select = {
+        let options = options.clone();
+        let onselect = onselect.clone();
+        let open = open.clone();
+        let query = query.clone();
+        Callback::from(move |value: T| {
+            save_recent(&options, value);
+            open.set(false);
+            query.set(String::new());
+            onselect.emit(value);
+        })
+    };
+
+    let on_open = {
+        let open = open.clone();
+        let highlighted = highlighted.clone();
+        Callback::from(move |_: MouseEvent| {
+            highlighted.set(0);
+            open.set(true);
+        })
+    };
+
+    let on_close = {
+        let open = open.clone();
+        let query = query.clone();
+        Callback::from(move |_: FocusEvent| {
+            open.set(false);
+            query.set(String::new());
+        })
+    };
+
+    let on_input = {
+        let query = query.clone();
+        let highlighted = highlighted.clone();
+        Callback::from(move |e: InputEvent| {
+            highlighted.set(0);
+            query.set(e.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let on_keydown = {
+        let entries = entries.clone();
+        let highlighted = highlighted.clone();
+        let select = select.clone();
+        let open = open.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            let last = entries.len().saturating_sub(1);
+            match e.key().as_str() {
+                "ArrowDown" => highlighted.set((*highlighted + 1).min(last)),
+                "ArrowUp" => highlighted.set(highlighted.saturating_sub(1)),
+                "Enter" => {
+                    if let Some(&(_, value)) = entries.get(*highlighted) {
+                        select.emit(value);
+                    }
+                }
+                "Escape" => open.set(false),
+                _ => return,
+            }
+            e.prevent_default();
+        })
+    };
+
+    let list = entries
+        .iter()
+        .enumerate()
+        .map(|(i, &(group, option))| {
+            let header = match group {
+                Some(group) if i == 0 || entries[i - 1].0 != Some(group) => html! {
+                    <li class={classes!("px-2", "text-xs", "text-std-400", "uppercase")}>{group}</li>
+                },
+                _ => html! {},
+            };
+            // Picked on mouse down, since the input loses focus and closes
+            // the dropdown before the click event is dispatched
+            let onmousedown = {
+                let select = select.clone();
+                Callback::from(move |e: MouseEvent| {
+                    e.prevent_default();
+                    select.emit(option);
+                })
+            };
+            let onmouseenter = {
+                let highlighted = highlighted.clone();
+                Callback::from(move |_: MouseEvent| highlighted.set(i))
+            };
+            let mut class = classes!("flex", "items-center", "gap-2", "px-2", "cursor-pointer");
+            if i == *highlighted {
+                class.push("bg-std-700");
+            }
+            html! {
+                <>
+                    {header}
+                    <li {class} {onmousedown} {onmouseenter}>
+                        <Image class={classes!("w-6", "h-6")} src={option.image_type()} />
+                        <span class={classes!("text-sm")}>{option.name()}</span>
+                    </li>
+                </>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class={classes!("relative")}>
+            {match *open {
+                true => html! {
+                    <>
+                        <input
+                            ref={input}
+                            type="text"
+                            class={classes!("bg-std-900", "text-sm", "w-56")}
+                            placeholder={*placeholder}
+                            value={(*query).clone()}
+                            oninput={on_input}
+                            onkeydown={on_keydown}
+                            onblur={on_close}
+                        />
+                        <ul class={classes!(
+                            "absolute", "z-30", "w-56", "max-h-72", "overflow-y-auto", "bg-std-900",
+                            "border", "border-std-700"
+                        )}>
+                            {list}
+                        </ul>
+                    </>
+                },
+                false => html! {
+                    <button
+                        class={classes!("flex", "items-center", "gap-2", "px-2", "bg-std-900", "text-sm")}
+                        onclick={on_open}
+                    >
+                        {match value {
+                            Some(value) => html! {
+                                <>
+                                    <Image class={classes!("w-6", "h-6")} src={value.image_type()} />
+                                    <span>{value.name()}</span>
+                                </>
+                            },
+                            None => html! { <span>{*placeholder}</span> },
+                        }}
+                    </button>
+                },
+            }}
+        </div>
+    }
+}
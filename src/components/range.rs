@@ -0,0 +1,50 @@
+use yew::prelude::*;
+
+/// Formats a damage range as `{min} – {max}`, or as a single
+/// number if both ends of the range are the same
+pub fn format_range(minimum: i32, maximum: i32) -> String {
+    match minimum == maximum {
+        true => minimum.to_string(),
+        false => format!("{minimum} – {maximum}"),
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct RangeValueProps {
+    pub minimum: i32,
+    pub maximum: i32,
+    /// Value that represents a full bar. If not set, no bar is displayed
+    #[prop_or_default]
+    pub scale: Option<i32>,
+}
+
+/// Displays a range of damage, optionally with a bar where the minimum
+/// value is stacked on top of the maximum one
+#[component]
+pub fn RangeValue(props: &RangeValueProps) -> Html {
+    let RangeValueProps {
+        minimum,
+        maximum,
+        scale,
+    } = props;
+
+    let bar = scale.filter(|scale| *scale > 0).map(|scale| {
+        let width = |value: i32| {
+            let percent = (value.max(0) as f64 * 100.0 / scale as f64).min(100.0);
+            format!("width: {percent:.1}%")
+        };
+        html! {
+            <div class={classes!("relative", "h-1", "w-full", "bg-std-800")}>
+                <div class={classes!("absolute", "h-full", "bg-std-500")} style={width(*maximum)} />
+                <div class={classes!("absolute", "h-full", "bg-std-200")} style={width(*minimum)} />
+            </div>
+        }
+    });
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-0.5")}>
+            <span>{format_range(*minimum, *maximum)}</span>
+            {bar}
+        </div>
+    }
+}
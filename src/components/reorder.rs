@@ -0,0 +1,76 @@
+use yew::prelude::*;
+
+/// Drag and drop handlers of one element of a reorderable list
+pub struct ReorderHandlers {
+    pub ondragstart: Callback<DragEvent>,
+    pub ondragover: Callback<DragEvent>,
+    pub ondrop: Callback<DragEvent>,
+    pub ondragend: Callback<DragEvent>,
+}
+
+/// Index of the element being dragged in a reorderable list
+#[derive(Clone)]
+pub struct Reorder {
+    dragged: UseStateHandle<Option<usize>>,
+    onmove: Callback<(usize, usize)>,
+}
+
+impl Reorder {
+    /// Handlers of the element at `index`. Dropping the dragged element over it
+    /// emits `onmove` with the indexes the element was moved from and to
+    pub fn handlers(&self, index: usize) -> ReorderHandlers {
+        let ondragstart = {
+            let dragged = self.dragged.clone();
+            Callback::from(move |e: DragEvent| {
+                // Some browsers do not start the drag without any data
+                if let Some(data) = e.data_transfer() {
+                    let _ = data.set_data("text/plain", &index.to_string());
+                }
+                dragged.set(Some(index));
+            })
+        };
+        let ondragover = Callback::from(|e: DragEvent| e.prevent_default());
+        let ondrop = {
+            let dragged = self.dragged.clone();
+            let onmove = self.onmove.clone();
+            Callback::from(move |e: DragEvent| {
+                e.prevent_default();
+                if let Some(from) = *dragged
+                    && from != index
+                {
+                    onmove.emit((from, index));
+                }
+                dragged.set(None);
+            })
+        };
+        let ondragend = {
+            let dragged = self.dragged.clone();
+            Callback::from(move |_: DragEvent| dragged.set(None))
+        };
+        ReorderHandlers {
+            ondragstart,
+            ondragover,
+            ondrop,
+            ondragend,
+        }
+    }
+}
+
+#[hook]
+pub fn use_reorder(onmove: Callback<(usize, usize)>) -> Reorder {
+    let dragged = use_state(|| None::<usize>);
+    Reorder { dragged, onmove }
+}
+
+/// New index of the element at `index` after the element at `from` is moved to `to`
+pub const fn moved_index(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < index && index <= to {
+        index - 1
+    } else if to <= index && index < from {
+        index + 1
+    } else {
+        index
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    calculator::{
+        Game,
+        columns::{Column, DamageBreakdown, damage_classes},
+    },
+    components::{image::Image, range::format_range, tables::spacer},
+    utils::ImageType,
+};
+use std::rc::Rc;
+use tutorlolv2_gen::DamageType;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct TableBodyProps {
+    pub game: Rc<Game>,
+    pub columns: Rc<[Column]>,
+    pub damage_types: Rc<[DamageType]>,
+    pub visible: Rc<[usize]>,
+    pub padding: (i32, i32),
+    /// Indexes of the enemies in the order their rows are displayed
+    pub order: Rc<[usize]>,
+    pub breakdowns: Rc<[DamageBreakdown]>,
+}
+
+/// One row for each enemy, with cells colored by the damage type of their column,
+/// followed by the total damage against that enemy split by damage type
+#[component]
+pub fn TableBody(props: &TableBodyProps) -> Html {
+    let TableBodyProps {
+        game,
+        columns,
+        damage_types,
+        visible,
+        padding,
+        order,
+        breakdowns,
+    } = props;
+
+    html! {
+        <tbody>
+            {order.iter().map(|&row| {
+                let enemy = &game.enemies[row];
+                let cells = visible
+                    .iter()
+                    .map(|&i| {
+                        let (minimum, maximum) = columns[i].range(&enemy.damages);
+                        let (text, _) = damage_classes(damage_types[i]);
+                        html! {
+                            <td class={classes!(text, "text-center", "px-1", "min-w-16")}>
+                                {format_range(minimum, maximum)}
+                            </td>
+                        }
+                    })
+                    .collect::<Html>();
+                html! {
+                    <tr>
+                        <td class={classes!("sticky", "left-0", "z-10", "bg-std-900", "min-w-12")}>
+                            <Image src={ImageType::Champion(enemy.champion_id)} focusable={true} />
+                        </td>
+                        {spacer(padding.0)}
+                        {cells}
+                        {spacer(padding.1)}
+                        {breakdowns[row].parts().into_iter().map(|(damage_type, _, value)| {
+                            let (text, _) = damage_classes(damage_type);
+                            html! {
+                                <td class={classes!(text, "text-center", "px-1", "font-bold")}>
+                                    {value}
+                                </td>
+                            }
+                        }).collect::<Html>()}
+                    </tr>
+                }
+            }).collect::<Html>()}
+        </tbody>
+    }
+}
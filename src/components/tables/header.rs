@@ -1,86 +1,83 @@
 use std::rc::Rc;
-use tutorlolv2_gen::{AbilityId, ChampionId, ItemId, MergeData, RuneId, TypeMetadata};
+use tutorlolv2_gen::{ChampionId, DamageType};
 use yew::prelude::*;
 
 use crate::{
-    components::image::Image,
-    utils::{AbilityKind, ImageType},
+    calculator::columns::{Column, DamageBreakdown, damage_classes},
+    components::{
+        image::Image,
+        tables::{
+            layout::{SortKey, TableLayout},
+            spacer,
+        },
+    },
 };
 
 #[derive(PartialEq, Properties)]
 pub struct TableHeaderProps {
     pub champion_id: ChampionId,
-    pub abilities_meta: Rc<[TypeMetadata<AbilityId>]>,
-    pub abilities_to_merge: Rc<[MergeData]>,
-    pub items_meta: Rc<[TypeMetadata<ItemId>]>,
-    pub runes_meta: Rc<[TypeMetadata<RuneId>]>,
+    pub columns: Rc<[Column]>,
+    pub damage_types: Rc<[DamageType]>,
+    /// Indexes of the columns that are currently rendered
+    pub visible: Rc<[usize]>,
+    /// Width in pixels of the columns skipped before and after the visible ones
+    pub padding: (i32, i32),
+    pub layout: TableLayout,
+    pub onsort: Callback<SortKey>,
 }
 
 #[component]
 pub fn TableHeader(props: &TableHeaderProps) -> Html {
     let TableHeaderProps {
         champion_id,
-        abilities_meta,
-        abilities_to_merge,
-        items_meta,
-        runes_meta,
+        columns,
+        damage_types,
+        visible,
+        padding,
+        layout,
+        onsort,
     } = props;
 
-    let abilities = {
-        let meta_len = abilities_meta.len();
-        let merge_len = abilities_to_merge.len();
-        let mut result = Vec::with_capacity(meta_len - merge_len);
-
-        let mut i = 0;
-        'outer: while i < meta_len {
-            let metadata = abilities_meta[i];
-            let mut ability_kind = AbilityKind::Normal(metadata.kind);
-            let mut j = 0;
-            'inner: while j < merge_len {
-                let merge = abilities_to_merge[j];
-                j += 1;
-                if merge.maximum_damage == i as u8 {
-                    i += 1;
-                    continue 'outer;
-                }
-                if merge.minimum_damage == i as u8 {
-                    ability_kind = AbilityKind::Alias(merge);
-                    break 'inner;
-                }
-            }
-
-            result.push(html! {
-                <th>
-                    <Image src={ImageType::Ability(*champion_id, ability_kind)} />
-                </th>
-            });
-            i += 1;
-        }
-
-        result
-    };
-
-    fn header<T: Copy + Into<ImageType>>(slice: &Rc<[TypeMetadata<T>]>) -> Html {
-        slice
-            .into_iter()
-            .map(|metadata| {
-                html! {
-                    <th>
-                        <Image src={metadata.kind.into()} />
-                    </th>
-                }
-            })
-            .collect::<Html>()
-    }
+    let sticky = classes!("sticky", "top-0", "z-10", "bg-std-900", "cursor-pointer");
 
     html! {
         <thead>
             <tr>
-                <th><Image src={ImageType::BasicAttack} /></th>
-                <th><Image src={ImageType::CritStrike} /></th>
-                {abilities}
-                {header(items_meta)}
-                {header(runes_meta)}
+                <th class={classes!("sticky", "top-0", "left-0", "z-20", "bg-std-900", "min-w-12")}></th>
+                {spacer(padding.0)}
+                {visible
+                    .iter()
+                    .map(|&i| {
+                        let key = SortKey::Column(columns[i].label());
+                        let arrow = layout.arrow(&key);
+                        let (_, border) = damage_classes(damage_types[i]);
+                        html! {
+                            <th
+                                class={classes!(sticky.clone(), "border-b-2", "min-w-16", border)}
+                                onclick={onsort.reform(move |_: MouseEvent| key.clone())}
+                            >
+                                <div class={classes!("flex", "items-center", "justify-center")}>
+                                    <Image src={columns[i].image_type(*champion_id)} />
+                                    {arrow}
+                                </div>
+                            </th>
+                        }
+                    })
+                    .collect::<Html>()}
+                {spacer(padding.1)}
+                {DamageBreakdown::default().parts().into_iter().enumerate().map(|(i, (damage_type, label, _))| {
+                    let key = SortKey::Total(i);
+                    let arrow = layout.arrow(&key);
+                    let (text, border) = damage_classes(damage_type);
+                    html! {
+                        <th
+                            class={classes!(sticky.clone(), "border-b-2", "text-sm", "px-1", text, border)}
+                            onclick={onsort.reform(move |_: MouseEvent| key.clone())}
+                        >
+                            {label}{arrow}
+                        </th>
+                    }
+                }).collect::<Html>()}
             </tr>
         </thead>
     }
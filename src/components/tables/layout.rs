@@ -0,0 +1,134 @@
+use crate::{calculator::columns::ColumnGroup, utils::storage};
+
+const STORAGE_KEY: &str = "damage_table_layout";
+
+/// Value by which the rows of the table can be sorted. Columns are identified
+/// by their [`crate::calculator::columns::Column::label`], since their indexes
+/// change with the champion and the build, while totals are identified by the
+/// index of their [`crate::calculator::columns::DamageBreakdown`] part
+#[derive(Clone, Debug, PartialEq)]
+pub enum SortKey {
+    Column(String),
+    Total(usize),
+}
+
+/// Layout of the damage table chosen by the user, persisted in the local storage
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableLayout {
+    pub hidden: Vec<ColumnGroup>,
+    /// Sorting key, and whether rows are sorted in descending order
+    pub sort: Option<(SortKey, bool)>,
+}
+
+impl TableLayout {
+    /// Reads the layout from the local storage
+    pub fn load() -> Self {
+        storage::load(STORAGE_KEY)
+            .map(|stored| Self::parse(&stored))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(STORAGE_KEY, &self.serialize());
+    }
+
+    /// Parses a layout stored as `hidden=Items,Runes;sort=cQ Empowered:desc`.
+    /// Column labels may hold any character, so the sort key is stored last
+    fn parse(stored: &str) -> Self {
+        let (hidden, sort) = stored.split_once(";sort=").unwrap_or((stored, ""));
+        let hidden = hidden
+            .strip_prefix("hidden=")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(ColumnGroup::from_name)
+            .collect();
+        let sort = sort.rsplit_once(':').and_then(|(key, order)| {
+            let key = match key.split_at_checked(1)? {
+                ("c", label) => SortKey::Column(label.to_string()),
+                ("t", index) => SortKey::Total(index.parse().ok()?),
+                _ => return None,
+            };
+            Some((key, order == "desc"))
+        });
+        Self { hidden, sort }
+    }
+
+    fn serialize(&self) -> String {
+        let hidden = self
+            .hidden
+            .iter()
+            .map(|group| group.name())
+            .collect::<Vec<_>>()
+            .join(",");
+        let sort = match &self.sort {
+            Some((SortKey::Column(label), desc)) => format!("c{label}:{}", Self::order(*desc)),
+            Some((SortKey::Total(i), desc)) => format!("t{i}:{}", Self::order(*desc)),
+            None => String::new(),
+        };
+        format!("hidden={hidden};sort={sort}")
+    }
+
+    const fn order(desc: bool) -> &'static str {
+        match desc {
+            true => "desc",
+            false => "asc",
+        }
+    }
+
+    pub fn is_hidden(&self, group: ColumnGroup) -> bool {
+        self.hidden.contains(&group)
+    }
+
+    pub fn toggle_group(&mut self, group: ColumnGroup) {
+        match self.hidden.iter().position(|hidden| *hidden == group) {
+            Some(i) => {
+                self.hidden.remove(i);
+            }
+            None => self.hidden.push(group),
+        }
+    }
+
+    /// Cycles the sorting of `key` between descending, ascending and unsorted
+    pub fn toggle_sort(&mut self, key: SortKey) {
+        self.sort = match self.sort.take() {
+            Some((current, true)) if current == key => Some((key, false)),
+            Some((current, false)) if current == key => None,
+            _ => Some((key, true)),
+        };
+    }
+
+    /// Arrow displayed next to the header of `key`, if rows are sorted by it
+    pub fn arrow(&self, key: &SortKey) -> Option<&'static str> {
+        match &self.sort {
+            Some((current, desc)) if current == key => Some(if *desc { "▼" } else { "▲" }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_is_stored_by_column_label() {
+        let layout = TableLayout {
+            hidden: vec![ColumnGroup::Items, ColumnGroup::Runes],
+            sort: Some((
+                SortKey::Column(String::from("Q Empowered: Second Cast")),
+                true,
+            )),
+        };
+        let stored = layout.serialize();
+        assert_eq!(
+            stored,
+            "hidden=Items,Runes;sort=cQ Empowered: Second Cast:desc"
+        );
+        assert_eq!(TableLayout::parse(&stored), layout);
+
+        let totals = TableLayout::parse("hidden=;sort=t2:asc");
+        assert!(totals.hidden.is_empty());
+        assert_eq!(totals.sort, Some((SortKey::Total(2), false)));
+        assert_eq!(TableLayout::parse("garbage"), TableLayout::default());
+    }
+}
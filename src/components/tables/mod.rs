@@ -1 +1,14 @@
+pub mod body;
 pub mod header;
+pub mod layout;
+pub mod table;
+
+use yew::prelude::*;
+
+/// Empty cell standing in for the columns that are not rendered
+fn spacer(width: i32) -> Html {
+    match width > 0 {
+        true => html! { <td style={format!("min-width: {width}px")}></td> },
+        false => html! {},
+    }
+}
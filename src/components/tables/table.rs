@@ -0,0 +1,200 @@
+use crate::{
+    calculator::{
+        Game,
+        columns::{ColumnGroup, DamageBreakdown, columns},
+    },
+    components::tables::{
+        body::TableBody,
+        header::TableHeader,
+        layout::{SortKey, TableLayout},
+    },
+};
+use std::rc::Rc;
+use wasm_bindgen::{JsCast, closure::Closure};
+use web_sys::Element;
+use yew::prelude::*;
+
+/// Width in pixels of each damage column, matching `min-w-16`
+const COLUMN_WIDTH: i32 = 64;
+/// Width in pixels of the sticky champion column, matching `min-w-12`
+const FIRST_COLUMN_WIDTH: i32 = 48;
+/// Columns rendered beyond each edge of the viewport
+const OVERSCAN: usize = 2;
+/// Tables with fewer columns than this are always fully rendered
+const VIRTUALIZE_AFTER: usize = 20;
+
+#[derive(PartialEq, Properties)]
+pub struct DamageTableProps {
+    pub game: Rc<Game>,
+}
+
+/// Damage of the current player against each enemy. Rows can be sorted by any
+/// column and groups of columns can be hidden, and both choices are persisted.
+/// The header and champion column stay in place while scrolling, and wide tables
+/// only render the columns within the viewport
+#[component]
+pub fn DamageTable(props: &DamageTableProps) -> Html {
+    let DamageTableProps { game } = props;
+
+    let layout = use_state(TableLayout::load);
+    // Horizontal scroll offset and width of the table container
+    let viewport = use_state(|| (0, 0));
+    let container = use_node_ref();
+
+    let measure = {
+        let container = container.clone();
+        let viewport = viewport.clone();
+        Callback::from(move |_: ()| {
+            if let Some(element) = container.cast::<Element>() {
+                viewport.set((element.scroll_left(), element.client_width()));
+            }
+        })
+    };
+
+    // The width of the container follows the window, so the visible columns
+    // are measured again on every resize
+    {
+        let measure = measure.clone();
+        use_effect_with((), move |_| {
+            measure.emit(());
+            let listener = Closure::<dyn Fn()>::new(move || measure.emit(()));
+            let window = web_sys::window();
+            if let Some(window) = &window {
+                let _ = window
+                    .add_event_listener_with_callback("resize", listener.as_ref().unchecked_ref());
+            }
+            move || {
+                if let Some(window) = &window {
+                    let _ = window.remove_event_listener_with_callback(
+                        "resize",
+                        listener.as_ref().unchecked_ref(),
+                    );
+                }
+            }
+        });
+    }
+
+    let onsort = {
+        let layout = layout.clone();
+        Callback::from(move |key: SortKey| {
+            let mut new = (*layout).clone();
+            new.toggle_sort(key);
+            new.save();
+            layout.set(new);
+        })
+    };
+
+    let columns: Rc<[_]> = columns(
+        &game.abilities_meta,
+        &game.abilities_to_merge,
+        &game.items_meta,
+        &game.runes_meta,
+    )
+    .into();
+    let damage_types: Rc<[_]> = columns
+        .iter()
+        .map(|column| column.damage_type(&game.abilities_meta, &game.items_meta, &game.runes_meta))
+        .collect();
+
+    let breakdowns: Rc<[_]> = game
+        .enemies
+        .iter()
+        .map(|enemy| {
+            let mut breakdown = DamageBreakdown::default();
+            for (column, damage_type) in columns.iter().zip(damage_types.iter()) {
+                breakdown.add(*damage_type, column.range(&enemy.damages).1);
+            }
+            breakdown
+        })
+        .collect();
+
+    let mut order = (0..game.enemies.len()).collect::<Vec<_>>();
+    if let Some((key, desc)) = &layout.sort {
+        // Columns of other champions and builds are missing, leaving rows unsorted
+        let column = match key {
+            SortKey::Column(label) => columns.iter().find(|column| column.label() == *label),
+            SortKey::Total(_) => None,
+        };
+        let value = |row: usize| match key {
+            SortKey::Column(_) => {
+                column.map_or(0, |column| column.range(&game.enemies[row].damages).1)
+            }
+            SortKey::Total(i) => breakdowns[row].parts().get(*i).map_or(0, |part| part.2),
+        };
+        order.sort_by_key(|&row| value(row));
+        if *desc {
+            order.reverse();
+        }
+    }
+
+    let shown = (0..columns.len())
+        .filter(|&i| !layout.is_hidden(columns[i].group()))
+        .collect::<Vec<_>>();
+    let (scroll_left, width) = *viewport;
+    let (visible, padding): (Rc<[usize]>, _) = match shown.len() > VIRTUALIZE_AFTER && width > 0 {
+        true => {
+            let first = ((scroll_left - FIRST_COLUMN_WIDTH).max(0) / COLUMN_WIDTH) as usize;
+            let first = first.saturating_sub(OVERSCAN).min(shown.len());
+            let count = (width / COLUMN_WIDTH) as usize + 2 * OVERSCAN;
+            let last = (first + count).min(shown.len());
+            (
+                shown[first..last].into(),
+                (
+                    first as i32 * COLUMN_WIDTH,
+                    (shown.len() - last) as i32 * COLUMN_WIDTH,
+                ),
+            )
+        }
+        false => (shown.into(), (0, 0)),
+    };
+
+    html! {
+        <div class={classes!("flex", "flex-col", "gap-1")}>
+            <div class={classes!("flex", "gap-1", "items-center")}>
+                {ColumnGroup::ALL.into_iter().map(|group| {
+                    let onclick = {
+                        let layout = layout.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let mut new = (*layout).clone();
+                            new.toggle_group(group);
+                            new.save();
+                            layout.set(new);
+                        })
+                    };
+                    let mut class = classes!("px-2", "text-sm");
+                    class.push(match layout.is_hidden(group) {
+                        true => classes!("bg-std-900", "text-std-500", "line-through"),
+                        false => classes!("bg-std-700"),
+                    });
+                    html! { <button {class} {onclick}>{group.name()}</button> }
+                }).collect::<Html>()}
+            </div>
+            <div
+                ref={container}
+                class={classes!("overflow-auto", "max-h-[32rem]", "max-w-full")}
+                onscroll={measure.reform(|_: Event| ())}
+            >
+                <table>
+                    <TableHeader
+                        champion_id={game.current_player.champion_id}
+                        columns={columns.clone()}
+                        damage_types={damage_types.clone()}
+                        visible={visible.clone()}
+                        {padding}
+                        layout={(*layout).clone()}
+                        {onsort}
+                    />
+                    <TableBody
+                        game={game.clone()}
+                        {columns}
+                        {damage_types}
+                        {visible}
+                        {padding}
+                        order={Rc::<[usize]>::from(order)}
+                        {breakdowns}
+                    />
+                </table>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,102 @@
+use crate::worker::{
+    RequestId, WorkerRequest, WorkerResponse,
+    client::{cancel_request, send_request},
+};
+use yew::prelude::*;
+
+/// A request sent to the worker by some component, along with its progress.
+/// Only one request runs at a time, so starting another one cancels the first
+#[derive(Clone)]
+pub struct WorkerTask {
+    running: UseStateHandle<Option<RequestId>>,
+    progress: UseStateHandle<(usize, usize)>,
+    error: UseStateHandle<Option<String>>,
+    ondone: Callback<WorkerResponse>,
+}
+
+impl WorkerTask {
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Sends `request` to the worker. Its final response is passed to the
+    /// `ondone` callback of [`use_worker_task`], unless it is an error
+    pub fn start(&self, request: WorkerRequest) {
+        self.cancel();
+
+        let on_response = {
+            let running = self.running.clone();
+            let progress = self.progress.clone();
+            let error = self.error.clone();
+            let ondone = self.ondone.clone();
+            Callback::from(move |response: WorkerResponse| {
+                if response.is_final() {
+                    running.set(None);
+                }
+                match response {
+                    WorkerResponse::Progress { done, total } => progress.set((done, total)),
+                    WorkerResponse::Error(e) => error.set(Some(e)),
+                    WorkerResponse::Cancelled => {}
+                    response => ondone.emit(response),
+                }
+            })
+        };
+
+        self.progress.set((0, 0));
+        match send_request(request, on_response) {
+            Some(id) => {
+                self.running.set(Some(id));
+                self.error.set(None);
+            }
+            None => self
+                .error
+                .set(Some(String::from("Failed to start the background task"))),
+        }
+    }
+
+    pub fn cancel(&self) {
+        if let Some(id) = *self.running {
+            cancel_request(id);
+        }
+        self.running.set(None);
+    }
+
+    /// Progress bar and cancel button while the request runs, or the error
+    /// of the last request
+    pub fn view(&self) -> Html {
+        if self.is_running() {
+            let (done, total) = *self.progress;
+            let task = self.clone();
+            let onclick = Callback::from(move |_: MouseEvent| task.cancel());
+            return html! {
+                <div class={classes!("flex", "gap-2", "items-center")}>
+                    <progress max={total.max(1).to_string()} value={done.to_string()} />
+                    <span class={classes!("text-sm")}>{format!("{done}/{total}")}</span>
+                    <button class={classes!("px-2", "py-1", "bg-std-800", "hover:bg-std-700")} {onclick}>
+                        {"Cancel"}
+                    </button>
+                </div>
+            };
+        }
+        match *self.error {
+            Some(ref error) => html! {
+                <span class={classes!("text-sm", "text-red-400")}>{error}</span>
+            },
+            None => html! {},
+        }
+    }
+}
+
+#[hook]
+pub fn use_worker_task(ondone: Callback<WorkerResponse>) -> WorkerTask {
+    let running = use_state(|| None::<RequestId>);
+    let progress = use_state(|| (0, 0));
+    let error = use_state(|| None::<String>);
+
+    WorkerTask {
+        running,
+        progress,
+        error,
+        ondone,
+    }
+}
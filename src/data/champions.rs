@@ -0,0 +1,136 @@
+//! Champion mechanics shown by the player and enemy editors. The generated data
+//! holds the formulas of each champion, but the calculator server reads
+//! [`crate::calculator::PlayerData::stacks`] and `is_mega_gnar` on its own, so
+//! what they mean for each champion is not exported anywhere. Unlike the rune
+//! and item tables, these labels and ranges are not patch data but describe
+//! how the server reads the input, so they are kept here and the tests below
+//! list every covered champion
+use crate::utils::variants;
+use tutorlolv2_gen::ChampionId;
+
+/// Describes what [`crate::calculator::PlayerData::stacks`] represents
+/// for a champion whose damage scales with some kind of stacks
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StackMechanic {
+    pub label: &'static str,
+    pub description: &'static str,
+    pub max: u32,
+    pub step: u32,
+}
+
+/// Champion-specific controls that should be displayed to the user. Field `form`
+/// holds the label of the alternative form toggled by
+/// [`crate::calculator::PlayerData::is_mega_gnar`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChampionMechanic {
+    pub stacks: Option<StackMechanic>,
+    pub form: Option<&'static str>,
+}
+
+impl ChampionMechanic {
+    const fn stacks(label: &'static str, description: &'static str, max: u32, step: u32) -> Self {
+        Self {
+            stacks: Some(StackMechanic {
+                label,
+                description,
+                max,
+                step,
+            }),
+            form: None,
+        }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.stacks.is_none() && self.form.is_none()
+    }
+}
+
+/// Returns which of the generic champion fields are meaningful for `champion_id`
+pub const fn mechanic(champion_id: ChampionId) -> ChampionMechanic {
+    match champion_id {
+        ChampionId::Veigar => ChampionMechanic::stacks(
+            "Phenomenal Evil",
+            "Ability power gained from Phenomenal Evil stacks",
+            1000,
+            1,
+        ),
+        ChampionId::Nasus => ChampionMechanic::stacks(
+            "Siphoning Strike",
+            "Bonus damage accumulated by Siphoning Strike",
+            2000,
+            3,
+        ),
+        ChampionId::Senna => {
+            ChampionMechanic::stacks("Mist", "Souls collected by Absolution", 200, 1)
+        }
+        ChampionId::Smolder => ChampionMechanic::stacks(
+            "Dragon Practice",
+            "Stacks of Dragon Practice, empowering Q at 25, 125 and 225",
+            600,
+            1,
+        ),
+        ChampionId::Thresh => {
+            ChampionMechanic::stacks("Damnation", "Souls collected by Damnation", 200, 1)
+        }
+        ChampionId::Kindred => {
+            ChampionMechanic::stacks("Mark of the Kindred", "Marks collected by Kindred", 30, 1)
+        }
+        ChampionId::Bard => {
+            ChampionMechanic::stacks("Traveler's Call", "Chimes collected by Bard", 100, 1)
+        }
+        ChampionId::Swain => ChampionMechanic::stacks(
+            "Ravenous Flock",
+            "Soul Fragments collected by Swain",
+            200,
+            1,
+        ),
+        ChampionId::Gnar => ChampionMechanic {
+            stacks: None,
+            form: Some("Mega Gnar"),
+        },
+        _ => ChampionMechanic {
+            stacks: None,
+            form: None,
+        },
+    }
+}
+
+/// Finds a champion either by its display name, such as `Kai'Sa`, or by its
+/// identifier, such as `Kaisa`, ignoring the case of the letters
+pub fn from_name(name: &str) -> Option<ChampionId> {
+    variants::<ChampionId>().find(|champion_id| {
+        champion_id.name().eq_ignore_ascii_case(name)
+            || format!("{champion_id:?}").eq_ignore_ascii_case(name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_every_covered_champion() {
+        let mut covered = variants::<ChampionId>()
+            .filter(|champion_id| !mechanic(*champion_id).is_empty())
+            .map(|champion_id| format!("{champion_id:?}"))
+            .collect::<Vec<_>>();
+        covered.sort();
+        assert_eq!(
+            covered,
+            [
+                "Bard", "Gnar", "Kindred", "Nasus", "Senna", "Smolder", "Swain", "Thresh", "Veigar"
+            ]
+        );
+    }
+
+    #[test]
+    fn stack_ranges_are_usable() {
+        for champion_id in variants::<ChampionId>() {
+            if let Some(stacks) = mechanic(champion_id).stacks {
+                assert!(stacks.step > 0, "{champion_id:?} has no step");
+                assert!(stacks.max >= stacks.step, "{champion_id:?} has no range");
+            }
+        }
+        assert_eq!(mechanic(ChampionId::Gnar).form, Some("Mega Gnar"));
+    }
+}
@@ -0,0 +1,239 @@
+//! Item build validation. The generated data only maps items to their Riot ids,
+//! so boots, unique passives and stacking items are kept here, keyed by Riot id,
+//! and checked against each other by the tests below
+use std::fmt;
+use tutorlolv2_gen::{ITEM_ID_TO_RIOT_ID, ItemId};
+
+/// Maximum number of items a champion can hold, excluding the trinket
+pub const MAX_ITEMS: usize = 6;
+
+/// Maximum number of boots a champion can hold
+pub const MAX_BOOTS: usize = 1;
+
+/// Groups of items whose unique passives do not stack, so only one item
+/// of each group can be held at a time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UniqueGroup {
+    LastWhisper,
+    Lifeline,
+    Spellblade,
+    Hydra,
+    ManaCharge,
+    Blight,
+    Quicksilver,
+    Annul,
+}
+
+impl UniqueGroup {
+    pub const ALL: [Self; 8] = [
+        Self::LastWhisper,
+        Self::Lifeline,
+        Self::Spellblade,
+        Self::Hydra,
+        Self::ManaCharge,
+        Self::Blight,
+        Self::Quicksilver,
+        Self::Annul,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::LastWhisper => "Last Whisper",
+            Self::Lifeline => "Lifeline",
+            Self::Spellblade => "Spellblade",
+            Self::Hydra => "Hydra",
+            Self::ManaCharge => "Mana Charge",
+            Self::Blight => "Blight",
+            Self::Quicksilver => "Quicksilver",
+            Self::Annul => "Annul",
+        }
+    }
+
+    pub const fn riot_ids(self) -> &'static [u32] {
+        match self {
+            Self::LastWhisper => &[3035, 3033, 3036],
+            Self::Lifeline => &[3053, 3156, 6673],
+            Self::Spellblade => &[3057, 3078, 3100, 3508, 6662],
+            Self::Hydra => &[3077, 3074, 3748, 6698],
+            Self::ManaCharge => &[3070, 3003, 3004, 3040, 3042, 3119, 3121],
+            Self::Blight => &[3135, 3137, 4630],
+            Self::Quicksilver => &[3139, 3140],
+            Self::Annul => &[3102, 3814],
+        }
+    }
+}
+
+/// Riot ids of every pair of boots
+const BOOTS: &[u32] = &[
+    1001, 3006, 3009, 3010, 3013, 3020, 3047, 3111, 3158, 3170, 3171, 3172, 3173, 3174, 3175,
+];
+
+/// Riot ids of items whose damage depends on a number of stacks, and the maximum
+/// number of stacks they can hold. [`u32::MAX`] is used for items without a limit
+const STACKABLE: &[(u32, u32)] = &[
+    (1082, 10),
+    (3003, 360),
+    (3004, 360),
+    (3041, 25),
+    (3070, 360),
+    (3071, 5),
+    (3084, u32::MAX),
+    (3119, 360),
+    (6657, 10),
+    (6672, 3),
+    (6697, u32::MAX),
+];
+
+const fn riot_id(item_id: ItemId) -> u32 {
+    ITEM_ID_TO_RIOT_ID[item_id as usize] as u32
+}
+
+/// Iterates over every variant of [`ItemId`]
+pub fn all_items() -> impl Iterator<Item = ItemId> {
+    (0..ItemId::VARIANTS as u16).filter_map(ItemId::from_u16)
+}
+
+/// Finds the [`ItemId`] associated to a Riot item id
+pub fn from_riot_id(riot_id: u32) -> Option<ItemId> {
+    all_items().find(|item_id| self::riot_id(*item_id) == riot_id)
+}
+
+pub fn unique_group(item_id: ItemId) -> Option<UniqueGroup> {
+    let riot_id = riot_id(item_id);
+    UniqueGroup::ALL
+        .into_iter()
+        .find(|group| group.riot_ids().contains(&riot_id))
+}
+
+/// Returns the maximum number of stacks of an item, or [`None`] if it does not stack
+pub fn max_stacks(item_id: ItemId) -> Option<u32> {
+    let riot_id = riot_id(item_id);
+    STACKABLE
+        .iter()
+        .find_map(|&(id, max)| (id == riot_id).then_some(max))
+}
+
+pub fn is_boots(item_id: ItemId) -> bool {
+    BOOTS.contains(&riot_id(item_id))
+}
+
+/// Reasons that make an item build impossible to be held in game. The generated
+/// data does not expose item tiers, so legendary uniqueness is enforced only
+/// through the known [`UniqueGroup`]s
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ItemError {
+    FullInventory,
+    TooManyBoots(ItemId),
+    UniqueGroup(UniqueGroup, ItemId, ItemId),
+}
+
+impl fmt::Display for ItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FullInventory => write!(f, "Only {MAX_ITEMS} items can be held"),
+            Self::TooManyBoots(item_id) => write!(
+                f,
+                "{} can not be held together with other boots",
+                item_id.name()
+            ),
+            Self::UniqueGroup(group, a, b) => write!(
+                f,
+                "{} and {} share the {} unique passive",
+                a.name(),
+                b.name(),
+                group.name()
+            ),
+        }
+    }
+}
+
+/// Checks if `item_id` conflicts with any of the `items`, ignoring the inventory size
+fn check_conflicts(items: &[ItemId], item_id: ItemId) -> Result<(), ItemError> {
+    if is_boots(item_id) && items.iter().filter(|other| is_boots(**other)).count() >= MAX_BOOTS {
+        return Err(ItemError::TooManyBoots(item_id));
+    }
+    if let Some(group) = unique_group(item_id)
+        && let Some(other) = items
+            .iter()
+            .find(|other| unique_group(**other) == Some(group))
+    {
+        return Err(ItemError::UniqueGroup(group, *other, item_id));
+    }
+    Ok(())
+}
+
+/// Checks if `item_id` can be added to a build that already holds `items`
+pub fn validate_insert(items: &[ItemId], item_id: ItemId) -> Result<(), ItemError> {
+    if items.len() >= MAX_ITEMS {
+        return Err(ItemError::FullInventory);
+    }
+    check_conflicts(items, item_id)
+}
+
+/// Returns every error found in `items`, checking each item against the ones
+/// that come before it. Builds that did not come from [`validate_insert`], such
+/// as the ones read from the game client, may contain any number of errors
+pub fn validate_build(items: &[ItemId]) -> Vec<ItemError> {
+    let mut errors = items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &item_id)| check_conflicts(&items[..i], item_id).err())
+        .collect::<Vec<_>>();
+    if items.len() > MAX_ITEMS {
+        errors.push(ItemError::FullInventory);
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_belong_to_a_single_group() {
+        let ids = UniqueGroup::ALL
+            .into_iter()
+            .flat_map(|group| group.riot_ids().iter().copied())
+            .collect::<Vec<_>>();
+        for (i, id) in ids.iter().enumerate() {
+            assert!(!ids[..i].contains(id), "{id} is in two unique groups");
+        }
+    }
+
+    #[test]
+    fn tables_have_no_duplicates() {
+        for (i, id) in BOOTS.iter().enumerate() {
+            assert!(!BOOTS[..i].contains(id), "{id} is listed twice as boots");
+        }
+        for (i, (id, max)) in STACKABLE.iter().enumerate() {
+            assert!(!STACKABLE[..i].iter().any(|(other, _)| other == id));
+            assert!(*max > 0);
+            assert!(!BOOTS.contains(id));
+        }
+    }
+
+    #[test]
+    fn riot_ids_map_back_to_items() {
+        for item_id in all_items() {
+            assert_eq!(from_riot_id(riot_id(item_id)), Some(item_id));
+        }
+    }
+
+    #[test]
+    fn rejects_conflicting_items() {
+        let mut grouped = all_items().filter(|item_id| unique_group(*item_id).is_some());
+        if let Some(item_id) = grouped.next() {
+            let group = unique_group(item_id).unwrap();
+            assert_eq!(
+                validate_insert(&[item_id], item_id),
+                Err(ItemError::UniqueGroup(group, item_id, item_id))
+            );
+            assert_eq!(validate_build(&[item_id, item_id]).len(), 1);
+        }
+        let full = [all_items().next().unwrap(); MAX_ITEMS];
+        assert_eq!(
+            validate_insert(&full, full[0]),
+            Err(ItemError::FullInventory)
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod champions;
+pub mod items;
+pub mod runes;
@@ -0,0 +1,373 @@
+//! Rune trees and page validation. The generated data only maps runes to their
+//! Riot ids, so the layout of the trees and the runes that stack are kept here,
+//! keyed by Riot id, and checked against each other by the tests below
+use std::fmt;
+use tutorlolv2_gen::{RUNE_ID_TO_RIOT_ID, RuneId};
+
+/// Number of rows in a rune tree, including the keystone row
+pub const ROWS: usize = 4;
+
+/// Index of the keystone row in [`RuneTree::rows`]
+pub const KEYSTONE_ROW: usize = 0;
+
+/// Maximum number of runes that can be taken from the secondary tree
+pub const SECONDARY_RUNES: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RuneTree {
+    Precision,
+    Domination,
+    Sorcery,
+    Resolve,
+    Inspiration,
+}
+
+impl RuneTree {
+    pub const ALL: [Self; 5] = [
+        Self::Precision,
+        Self::Domination,
+        Self::Sorcery,
+        Self::Resolve,
+        Self::Inspiration,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Precision => "Precision",
+            Self::Domination => "Domination",
+            Self::Sorcery => "Sorcery",
+            Self::Resolve => "Resolve",
+            Self::Inspiration => "Inspiration",
+        }
+    }
+
+    /// Riot ids of the runes in each row of this tree. Index [`KEYSTONE_ROW`]
+    /// holds the keystones, and the remaining rows can also be picked from the
+    /// secondary tree
+    pub const fn rows(self) -> [&'static [u32]; ROWS] {
+        match self {
+            Self::Precision => [
+                &[8005, 8008, 8021, 8010],
+                &[9101, 9111, 8009],
+                &[9104, 9105, 9103],
+                &[8014, 8017, 8299],
+            ],
+            Self::Domination => [
+                &[8112, 8128, 9923],
+                &[8126, 8139, 8143],
+                &[8137, 8140, 8141],
+                &[8135, 8105, 8106],
+            ],
+            Self::Sorcery => [
+                &[8214, 8229, 8230],
+                &[8224, 8226, 8275],
+                &[8210, 8234, 8233],
+                &[8237, 8232, 8236],
+            ],
+            Self::Resolve => [
+                &[8437, 8439, 8465],
+                &[8446, 8463, 8401],
+                &[8429, 8444, 8473],
+                &[8451, 8453, 8242],
+            ],
+            Self::Inspiration => [
+                &[8351, 8360, 8369],
+                &[8306, 8304, 8321],
+                &[8313, 8352, 8345],
+                &[8347, 8410, 8316],
+            ],
+        }
+    }
+
+    /// Returns every [`RuneId`] that belongs to the given row of this tree. Runes
+    /// that have no damage associated to them are not part of [`RuneId`], so some
+    /// rows may be empty
+    pub fn runes(self, row: usize) -> Vec<RuneId> {
+        all_runes()
+            .filter(|rune_id| rune_slot(*rune_id) == Some(RuneSlot { tree: self, row }))
+            .collect()
+    }
+}
+
+/// Position of a rune within the rune trees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RuneSlot {
+    pub tree: RuneTree,
+    pub row: usize,
+}
+
+/// Riot ids of runes whose damage depends on a number of stacks, and the maximum
+/// number of stacks they can hold. [`u32::MAX`] is used for runes without a limit
+const STACKABLE: &[(u32, u32)] = &[
+    (8005, 3),
+    (8008, 6),
+    (8010, 12),
+    (8128, u32::MAX),
+    (8236, u32::MAX),
+    (9103, 15),
+    (9104, 10),
+    (9105, 10),
+];
+
+/// Returns the maximum number of stacks of a rune, or [`None`] if it does not stack
+pub fn max_stacks(rune_id: RuneId) -> Option<u32> {
+    let riot_id = RUNE_ID_TO_RIOT_ID[rune_id as usize] as u32;
+    STACKABLE
+        .iter()
+        .find_map(|&(id, max)| (id == riot_id).then_some(max))
+}
+
+/// Iterates over every variant of [`RuneId`]
+pub fn all_runes() -> impl Iterator<Item = RuneId> {
+    (0..RuneId::VARIANTS as u8).filter_map(RuneId::from_u8)
+}
+
+/// Finds the [`RuneId`] associated to a Riot rune id
+pub fn from_riot_id(riot_id: u32) -> Option<RuneId> {
+    all_runes().find(|rune_id| RUNE_ID_TO_RIOT_ID[*rune_id as usize] as u32 == riot_id)
+}
+
+/// Finds the tree and row of a rune. Returns [`None`] if this rune
+/// is not present in any of the rune trees
+pub fn rune_slot(rune_id: RuneId) -> Option<RuneSlot> {
+    let riot_id = RUNE_ID_TO_RIOT_ID[rune_id as usize] as u32;
+    RuneTree::ALL.into_iter().find_map(|tree| {
+        tree.rows()
+            .iter()
+            .position(|row| row.contains(&riot_id))
+            .map(|row| RuneSlot { tree, row })
+    })
+}
+
+/// Reasons that make a rune page illegal
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RuneError {
+    UnknownRune(RuneId),
+    DuplicateRune(RuneId),
+    MultipleKeystones,
+    SameRow(RuneId, RuneId),
+    TooManyTrees,
+    SecondaryKeystone(RuneId),
+    SecondaryTooManyRunes,
+}
+
+impl fmt::Display for RuneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownRune(rune_id) => {
+                write!(f, "{} does not belong to any rune tree", rune_id.name())
+            }
+            Self::DuplicateRune(rune_id) => write!(f, "{} was taken twice", rune_id.name()),
+            Self::MultipleKeystones => write!(f, "Only one keystone can be taken"),
+            Self::SameRow(a, b) => {
+                write!(f, "{} and {} are in the same row", a.name(), b.name())
+            }
+            Self::TooManyTrees => write!(f, "Runes can only come from two trees"),
+            Self::SecondaryKeystone(rune_id) => {
+                write!(f, "{} is a keystone of the secondary tree", rune_id.name())
+            }
+            Self::SecondaryTooManyRunes => write!(
+                f,
+                "Only {SECONDARY_RUNES} runes can be taken from the secondary tree"
+            ),
+        }
+    }
+}
+
+/// Rune page split into its primary and secondary trees
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunePage {
+    pub primary: Option<RuneTree>,
+    pub secondary: Option<RuneTree>,
+    pub errors: Vec<RuneError>,
+}
+
+impl RunePage {
+    /// Groups `runes` into trees and checks if they form a legal page. The primary
+    /// tree is the one holding the keystone, or the one with most runes if there is
+    /// none. Pages may be incomplete, since only runes with damage exist in [`RuneId`]
+    pub fn new(runes: &[RuneId]) -> Self {
+        let mut page = Self::default();
+        let mut slots = Vec::with_capacity(runes.len());
+
+        for (i, &rune_id) in runes.iter().enumerate() {
+            if runes[..i].contains(&rune_id) {
+                page.errors.push(RuneError::DuplicateRune(rune_id));
+                continue;
+            }
+            match rune_slot(rune_id) {
+                Some(slot) => slots.push((rune_id, slot)),
+                None => page.errors.push(RuneError::UnknownRune(rune_id)),
+            }
+        }
+
+        let keystones = slots
+            .iter()
+            .filter(|(_, slot)| slot.row == KEYSTONE_ROW)
+            .collect::<Vec<_>>();
+        if keystones.len() > 1 {
+            page.errors.push(RuneError::MultipleKeystones);
+        }
+
+        let count = |tree| slots.iter().filter(|(_, slot)| slot.tree == tree).count();
+        let mut trees = RuneTree::ALL
+            .into_iter()
+            .filter(|tree| count(*tree) > 0)
+            .collect::<Vec<_>>();
+        trees.sort_by_key(|tree| core::cmp::Reverse(count(*tree)));
+
+        page.primary = keystones
+            .first()
+            .map(|(_, slot)| slot.tree)
+            .or(trees.first().copied());
+        page.secondary = trees.into_iter().find(|tree| Some(*tree) != page.primary);
+
+        for (_, slot) in &slots {
+            if Some(slot.tree) != page.primary && Some(slot.tree) != page.secondary {
+                page.errors.push(RuneError::TooManyTrees);
+                break;
+            }
+        }
+
+        for (i, (a, slot_a)) in slots.iter().enumerate() {
+            if slot_a.row != KEYSTONE_ROW
+                && let Some((b, _)) = slots[..i].iter().find(|(_, slot_b)| slot_a == slot_b)
+            {
+                page.errors.push(RuneError::SameRow(*b, *a));
+            }
+        }
+
+        let secondary = slots
+            .iter()
+            .filter(|(_, slot)| Some(slot.tree) == page.secondary)
+            .collect::<Vec<_>>();
+        for (rune_id, slot) in &secondary {
+            if slot.row == KEYSTONE_ROW {
+                page.errors.push(RuneError::SecondaryKeystone(*rune_id));
+            }
+        }
+        if secondary.len() > SECONDARY_RUNES {
+            page.errors.push(RuneError::SecondaryTooManyRunes);
+        }
+
+        page
+    }
+
+    pub fn is_legal(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Returns the page that results from selecting `rune_id` in `runes`. Runes in the
+/// same slot are replaced, and picking a third rune from the secondary tree drops
+/// the oldest secondary one, mimicking the behavior of the game client
+pub fn select_rune(runes: &[RuneId], rune_id: RuneId, secondary: Option<RuneTree>) -> Vec<RuneId> {
+    let Some(slot) = rune_slot(rune_id) else {
+        return runes.to_vec();
+    };
+
+    let mut result = runes
+        .iter()
+        .copied()
+        .filter(|other| match rune_slot(*other) {
+            Some(other_slot) => other_slot != slot,
+            None => false,
+        })
+        .collect::<Vec<_>>();
+
+    if Some(slot.tree) == secondary {
+        let taken = result
+            .iter()
+            .filter(|other| rune_slot(**other).map(|s| s.tree) == secondary)
+            .count();
+        if taken >= SECONDARY_RUNES
+            && let Some(oldest) = result
+                .iter()
+                .position(|other| rune_slot(*other).map(|s| s.tree) == secondary)
+        {
+            result.remove(oldest);
+        }
+    }
+
+    result.push(rune_id);
+    result
+}
+
+/// Keeps only the runes of `runes` that belong to the `primary` or `secondary` trees.
+/// Used when the user switches one of the trees of the page
+pub fn retain_trees(
+    runes: &[RuneId],
+    primary: Option<RuneTree>,
+    secondary: Option<RuneTree>,
+) -> Vec<RuneId> {
+    runes
+        .iter()
+        .copied()
+        .filter(|rune_id| match rune_slot(*rune_id) {
+            Some(slot) if Some(slot.tree) == primary => true,
+            Some(slot) => Some(slot.tree) == secondary && slot.row != KEYSTONE_ROW,
+            None => false,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn riot_ids() -> impl Iterator<Item = u32> {
+        RuneTree::ALL
+            .into_iter()
+            .flat_map(|tree| tree.rows())
+            .flat_map(|row| row.iter().copied())
+    }
+
+    fn rune(riot_id: u32) -> RuneId {
+        from_riot_id(riot_id).unwrap()
+    }
+
+    #[test]
+    fn runes_belong_to_a_single_slot() {
+        let ids = riot_ids().collect::<Vec<_>>();
+        for (i, id) in ids.iter().enumerate() {
+            assert!(!ids[..i].contains(id), "{id} is listed twice");
+        }
+    }
+
+    #[test]
+    fn stackable_runes_are_in_a_tree() {
+        for (id, max) in STACKABLE {
+            assert!(riot_ids().any(|other| other == *id), "{id} is in no tree");
+            assert!(*max > 0);
+        }
+    }
+
+    #[test]
+    fn finds_rune_slots() {
+        assert_eq!(
+            rune_slot(rune(8112)),
+            Some(RuneSlot {
+                tree: RuneTree::Domination,
+                row: KEYSTONE_ROW,
+            })
+        );
+        assert_eq!(
+            rune_slot(rune(8237)),
+            Some(RuneSlot {
+                tree: RuneTree::Sorcery,
+                row: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn validates_pages() {
+        let page = RunePage::new(&[rune(8112), rune(8126), rune(8237)]);
+        assert!(page.is_legal(), "{:?}", page.errors);
+        assert_eq!(page.primary, Some(RuneTree::Domination));
+        assert_eq!(page.secondary, Some(RuneTree::Sorcery));
+
+        let page = RunePage::new(&[rune(8112), rune(8214)]);
+        assert!(page.errors.contains(&RuneError::MultipleKeystones));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod calculator;
+pub mod components;
+pub mod data;
+pub mod documentation;
+pub mod model;
+pub mod utils;
+pub mod worker;
@@ -1,22 +1,26 @@
-use crate::{
-    calculator::page::Calculator, documentation::page::Documentation, utils::cache::init_cache,
+use tutorlolv2_web::{
+    calculator::page::Calculator,
+    components::{docs::DocsProvider, inspector::InspectorProvider},
+    documentation::page::Documentation,
+    utils::cache::init_cache,
 };
 use yew::prelude::*;
 
-mod calculator;
-mod components;
-mod documentation;
-mod model;
-mod utils;
-
 #[component]
 fn App() -> Html {
     html! {
-        <Calculator />
+        <InspectorProvider>
+            <DocsProvider>
+                <Calculator />
+            </DocsProvider>
+        </InspectorProvider>
     }
 }
 
 fn main() {
+    let performance = web_sys::window().and_then(|window| window.performance());
+    let now = || performance.as_ref().map_or(0.0, |p| p.now());
+    let start = now();
     yew::Renderer::<App>::new().render();
-    init_cache();
+    init_cache(now() - start);
 }
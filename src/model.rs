@@ -31,6 +31,24 @@ pub enum Team {
     Red,
 }
 
+impl Team {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Team::Blue => "Blue",
+            Team::Red => "Red",
+        }
+    }
+}
+
+impl From<&str> for Team {
+    fn from(value: &str) -> Self {
+        match value {
+            "CHAOS" => Team::Red,
+            _ => Team::Blue,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Decode, PartialEq)]
 pub struct RangeDamage {
     pub minimum_damage: i32,
@@ -84,7 +102,7 @@ pub struct Damages {
 /// Wrapper around the type [`u32`], whose first [`Self::DISC_BITS`] are used to
 /// identify the enum type of the current value, which is either [`ItemId`] or [`RuneId`],
 /// and the remaining [`Self::VAL_BITS`] are used to store the actual number of stacks held
-#[derive(Clone, Copy, Debug, Encode, PartialEq)]
+#[derive(Clone, Copy, Debug, Decode, Encode, PartialEq)]
 #[repr(transparent)]
 pub struct ValueException(u32);
 
@@ -125,10 +143,20 @@ impl ValueException {
 
     /// If the value to be stored is greater than [`Self::VAL_MASK`],
     /// the value is truncated
-    const fn truncate_value(v: u32) -> u32 {
+    pub const fn truncate_value(v: u32) -> u32 {
         v & Self::VAL_MASK
     }
 
+    /// Returns if storing `v` would lose information in [`Self::truncate_value`]
+    pub const fn would_truncate(v: u32) -> bool {
+        v > Self::VAL_MASK
+    }
+
+    /// Returns a copy of [`Self`] holding the same enum id, but with `v` stacks
+    pub const fn with_stacks(self, v: u32) -> Self {
+        Self((self.0 & Self::DISC_MASK) | Self::truncate_value(v))
+    }
+
     /// Creates a new instance of [`Self`] from a [`RuneId`] and a number of stacks
     pub const fn pack_rune_id(r: RuneId, v: u32) -> Self {
         let disc = (r as u32) & Self::DISC_LOW_MASK;
@@ -1,53 +1,504 @@
-use brotli::BrotliDecompress;
-use std::io::Write;
-use tutorlolv2_gen::{BLOCK, BLOCK_SIZE};
-use wasm_bindgen::prelude::wasm_bindgen;
+//! Formula cache, holding the HTML of every formula of the generated data. It is
+//! stored as a single Brotli stream, [`BLOCK`], which is decompressed by the
+//! worker at startup. If the worker is not available, it is decompressed in
+//! chunks while the browser is idle instead, and ranges that were not
+//! decompressed yet are decompressed on demand when requested. While the worker
+//! runs, the main thread never decompresses, and requested ranges are missing
+//! until [`notify_when_ready`] reports that the cache arrived
+use crate::worker::{WorkerRequest, WorkerResponse, client::send_request};
+use brotli::Decompressor;
+use std::{cell::RefCell, collections::HashMap, fmt, io::Read, rc::Rc};
+use tutorlolv2_gen::{
+    ABILITY_FORMULAS, BASIC_ATTACK_OFFSET, BLOCK, BLOCK_SIZE, CHAMPION_FORMULAS,
+    CRITICAL_STRIKE_OFFSET, ITEM_FORMULAS, RUNE_FORMULAS,
+};
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use web_sys::IdleDeadline;
+use yew::Callback;
 
-pub static mut CACHE: [u8; BLOCK_SIZE] = [0; _];
+/// Number of decompressed bytes produced by each step
+const CHUNK_SIZE: usize = 64 * 1024;
 
-pub struct FixedBuffer<const N: usize> {
-    buffer: &'static mut [u8; N],
-    position: usize,
+/// Size of the buffer of compressed input used by the decoder
+const INPUT_BUFFER_SIZE: usize = 4096;
+
+/// Time in milliseconds spent decompressing in each callback when the
+/// browser does not support idle callbacks
+const FALLBACK_BUDGET: f64 = 8.0;
+
+/// Reasons that make the cache unusable, either completely or for some ranges
+#[derive(Debug)]
+pub enum CacheError {
+    Decompression(std::io::Error),
+    /// The stream ended before [`BLOCK_SIZE`] bytes, holding the actual size
+    TooShort(usize),
+    /// The stream holds more than [`BLOCK_SIZE`] bytes
+    TooLong,
+    /// Some formula offset is out of bounds, or is not a valid UTF-8 slice
+    InvalidRange(u32, u32),
 }
 
-impl<const N: usize> Write for FixedBuffer<N> {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        unsafe {
-            let destination_ptr = self.buffer.as_mut_ptr().add(self.position);
-            let source_ptr = buf.as_ptr();
-            core::ptr::copy_nonoverlapping(source_ptr, destination_ptr, buf.len());
-            self.position += buf.len();
-            Ok(buf.len())
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decompression(e) => write!(f, "Failed to decompress cache: {e}"),
+            Self::TooShort(size) => write!(
+                f,
+                "Cache has {size} bytes, but {BLOCK_SIZE} bytes were expected"
+            ),
+            Self::TooLong => write!(f, "Cache has more than the expected {BLOCK_SIZE} bytes"),
+            Self::InvalidRange(start, end) => {
+                write!(f, "Formula range {start}..{end} is not valid in the cache")
+            }
         }
     }
+}
 
-    fn flush(&mut self) -> std::io::Result<()> {
-        Ok(())
+/// Durations of the startup work, in milliseconds
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StartupTimings {
+    /// Time taken by the first render
+    pub render: f64,
+    /// Time spent decompressing, summed across every chunk
+    pub decompression: f64,
+    /// Time between the start of the decompression and its end
+    pub total: f64,
+    pub chunks: u32,
+    /// Whether the cache was decompressed by the worker. Only the chunks that
+    /// were requested before the worker finished were decompressed here
+    pub in_worker: bool,
+}
+
+impl StartupTimings {
+    fn report(&self) -> String {
+        let chunks = match self.in_worker {
+            true => format!("the worker and {} chunks", self.chunks),
+            false => format!("{} chunks", self.chunks),
+        };
+        format!(
+            "Startup: render {:.1} ms, cache decompressed in {chunks} taking \
+            {:.1} ms of work over {:.1} ms",
+            self.render, self.decompression, self.total
+        )
     }
 }
 
-#[wasm_bindgen]
-pub fn cache_ptr() -> *const u8 {
-    unsafe { CACHE.as_ptr() }
+/// Decompressed contents of [`BLOCK`]. Bytes are only ever appended, never
+/// past [`BLOCK_SIZE`], and once the stream ends every formula range is
+/// validated. Ranges that failed validation are never returned
+pub struct FormulaCache {
+    decoder: Option<Decompressor<&'static [u8]>>,
+    /// Decompressed bytes. The capacity is reserved upfront, so appending
+    /// chunks never moves the buffer
+    data: Vec<u8>,
+    errors: Vec<CacheError>,
+    timings: StartupTimings,
+    started: Option<f64>,
+    /// Whether the worker is decompressing the cache, in which case ranges
+    /// are not decompressed on demand
+    in_worker: bool,
+    /// Callbacks to call once the worker is done, see [`notify_when_ready`]
+    waiting: Vec<Callback<()>>,
+    /// Texts returned by [`get_cache`], shared so they are only copied once
+    texts: HashMap<Vec<(u32, u32)>, Rc<str>>,
+}
+
+thread_local! {
+    static CACHE: RefCell<FormulaCache> = RefCell::new(FormulaCache::default());
+}
+
+#[cfg(target_arch = "wasm32")]
+fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
+
+/// There is no window outside of the browser, so native builds such as the
+/// tests report no timings
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> f64 {
+    0.0
+}
+
+/// Every formula range of the generated data
+fn formula_ranges() -> impl Iterator<Item = (u32, u32)> {
+    CHAMPION_FORMULAS
+        .iter()
+        .chain(ITEM_FORMULAS.iter())
+        .chain(RUNE_FORMULAS.iter())
+        .chain(ABILITY_FORMULAS.iter().flat_map(|formulas| formulas.iter()))
+        .copied()
+        .chain([BASIC_ATTACK_OFFSET, CRITICAL_STRIKE_OFFSET])
 }
 
-#[wasm_bindgen]
-pub fn cache_len() -> usize {
-    BLOCK_SIZE
+impl Default for FormulaCache {
+    fn default() -> Self {
+        Self {
+            decoder: Some(Decompressor::new(&BLOCK[..], INPUT_BUFFER_SIZE)),
+            data: Vec::with_capacity(BLOCK_SIZE),
+            errors: Vec::new(),
+            timings: StartupTimings::default(),
+            started: None,
+            in_worker: false,
+            waiting: Vec::new(),
+            texts: HashMap::new(),
+        }
+    }
 }
 
-#[cold]
-pub fn init_cache() {
-    web_sys::console::time();
-    unsafe {
-        BrotliDecompress(
-            &mut (&BLOCK as &[u8]),
-            &mut (&mut FixedBuffer {
-                buffer: &mut CACHE,
-                position: 0,
+impl FormulaCache {
+    pub fn is_done(&self) -> bool {
+        self.decoder.is_none()
+    }
+
+    /// Decompresses the next chunk. The decoder is dropped once the stream
+    /// ends or fails, and the cache is then validated
+    fn step(&mut self) {
+        let Some(decoder) = &mut self.decoder else {
+            return;
+        };
+        let start = now();
+        let len = self.data.len();
+        let result = match len < BLOCK_SIZE {
+            true => {
+                self.data.resize((len + CHUNK_SIZE).min(BLOCK_SIZE), 0);
+                let result = decoder.read(&mut self.data[len..]);
+                self.data.truncate(len + *result.as_ref().unwrap_or(&0));
+                result.map(|n| (n == 0).then_some(len))
+            }
+            // Any byte past the expected size means that the generated
+            // data is inconsistent, so the whole cache is rejected
+            false => decoder.read(&mut [0]).map(|n| match n {
+                0 => Some(len),
+                _ => None,
             }),
-        )
-        .unwrap_unchecked();
+        };
+        let finished = match result {
+            Ok(Some(size)) if size < BLOCK_SIZE => Err(CacheError::TooShort(size)),
+            Ok(Some(_)) => Ok(true),
+            Ok(None) if len >= BLOCK_SIZE => Err(CacheError::TooLong),
+            Ok(None) => Ok(false),
+            Err(e) => Err(CacheError::Decompression(e)),
+        };
+
+        let end = now();
+        self.timings.decompression += end - start;
+        self.timings.chunks += 1;
+        match finished {
+            Ok(false) => return,
+            Ok(true) => self.validate(),
+            Err(error) => {
+                if matches!(error, CacheError::TooLong) {
+                    self.data.clear();
+                }
+                self.errors.push(error);
+            }
+        }
+        self.finish(end);
+    }
+
+    /// Drops the decoder and reports the timings and errors. Caches that
+    /// were never started by [`init_cache`], such as the one used by the
+    /// worker, report nothing
+    fn finish(&mut self, end: f64) {
+        self.decoder = None;
+        let Some(started) = self.started else {
+            return;
+        };
+        self.timings.total = end - started;
+        for error in &self.errors {
+            web_sys::console::error_1(&error.to_string().into());
+        }
+        web_sys::console::info_1(&self.timings.report().into());
+    }
+
+    /// Replaces the contents of the cache with `bytes`, unless it was already
+    /// fully decompressed
+    fn install(&mut self, bytes: Vec<u8>) -> Result<(), CacheError> {
+        if self.is_done() {
+            return Ok(());
+        }
+        match bytes.len() {
+            size if size < BLOCK_SIZE => return Err(CacheError::TooShort(size)),
+            size if size > BLOCK_SIZE => return Err(CacheError::TooLong),
+            _ => {}
+        }
+        self.data = bytes;
+        self.validate();
+        self.timings.in_worker = true;
+        self.finish(now());
+        Ok(())
+    }
+
+    /// Checks that every formula range is within the cache and holds valid UTF-8
+    fn validate(&mut self) {
+        let invalid = formula_ranges()
+            .filter(|&range| self.slice(range).is_none())
+            .map(|(start, end)| CacheError::InvalidRange(start, end))
+            .collect::<Vec<_>>();
+        self.errors.extend(invalid);
+    }
+
+    fn load(&mut self, end: usize) {
+        while self.data.len() < end && !self.is_done() {
+            self.step();
+        }
+    }
+
+    fn slice(&self, (start, end): (u32, u32)) -> Option<&str> {
+        let bytes = self.data.get(start as usize..end as usize)?;
+        std::str::from_utf8(bytes).ok()
+    }
+
+    /// Returns the text at `start..end`, decompressing it first if needed,
+    /// unless the worker is decompressing the cache. Returns [`None`] if the
+    /// range is out of bounds, is not valid UTF-8 or is not available yet
+    pub fn get(&mut self, range: (u32, u32)) -> Option<&str> {
+        if !self.in_worker {
+            self.load(range.1 as usize);
+        }
+        self.slice(range)
+    }
+
+    /// Joins the texts of `ranges`, see [`get_cache_joined`]
+    fn text(&mut self, ranges: &[(u32, u32)]) -> Option<Rc<str>> {
+        if let Some(text) = self.texts.get(ranges) {
+            return Some(text.clone());
+        }
+        let (&first, rest) = ranges.split_first()?;
+        let mut text = self.get(first)?.to_string();
+        for &range in rest {
+            text.push_str(self.get(range).unwrap_or_default());
+        }
+        let text = Rc::<str>::from(text);
+        self.texts.insert(ranges.to_vec(), text.clone());
+        Some(text)
+    }
+}
+
+/// Decompresses chunks until `deadline` has no time left, and schedules
+/// another callback if the cache is not complete yet
+fn idle_step(deadline: JsValue) {
+    let deadline = deadline.dyn_into::<IdleDeadline>().ok();
+    let start = now();
+    let has_time = || match &deadline {
+        Some(deadline) => deadline.time_remaining() > 1.0,
+        None => now() - start < FALLBACK_BUDGET,
+    };
+    let done = CACHE.with_borrow_mut(|cache| {
+        while !cache.is_done() && has_time() {
+            cache.step();
+        }
+        cache.is_done()
+    });
+    if !done {
+        schedule();
+    }
+}
+
+fn schedule() {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let callback = Closure::once_into_js(idle_step);
+    let function = callback.unchecked_ref();
+    if window.request_idle_callback(function).is_err() {
+        let _ = window.set_timeout_with_callback(function);
+    }
+}
+
+/// Decompresses the whole cache at once, calling `on_progress` with the number
+/// of decompressed bytes and [`BLOCK_SIZE`] after each chunk. Used by the worker,
+/// whose bytes are validated again by the main thread once they are received
+pub fn decompress_cache(on_progress: impl Fn(usize, usize)) -> Result<Vec<u8>, CacheError> {
+    let mut cache = FormulaCache::default();
+    while !cache.is_done() {
+        cache.step();
+        on_progress(cache.data.len(), BLOCK_SIZE);
+    }
+    match cache
+        .errors
+        .into_iter()
+        .find(|error| !matches!(error, CacheError::InvalidRange(..)))
+    {
+        Some(error) => Err(error),
+        None => Ok(cache.data),
+    }
+}
+
+/// Handles the response to [`WorkerRequest::DecompressCache`], falling back
+/// to idle decompression if the worker failed. Either way, ranges can be
+/// requested again, so every waiting callback is called
+fn on_worker_response(response: WorkerResponse) {
+    let result = match response {
+        WorkerResponse::Cache(bytes) => {
+            CACHE.with_borrow_mut(|cache| cache.install(bytes).map_err(|e| e.to_string()))
+        }
+        WorkerResponse::Error(e) => Err(e),
+        _ => return,
+    };
+    if let Err(e) = result {
+        web_sys::console::error_1(&format!("Worker failed to decompress cache: {e}").into());
+        schedule();
+    }
+    let waiting = CACHE.with_borrow_mut(|cache| {
+        cache.in_worker = false;
+        std::mem::take(&mut cache.waiting)
+    });
+    for callback in waiting {
+        callback.emit(());
+    }
+}
+
+/// Starts decompressing the cache in the worker, or in idle callbacks if the
+/// worker is not available. `render` is the time taken by the first render,
+/// which is reported along with the decompression. Calling it again has no effect
+pub fn init_cache(render: f64) {
+    let first = CACHE.with_borrow_mut(|cache| {
+        if cache.started.is_some() {
+            return false;
+        }
+        cache.started = Some(now());
+        cache.timings.render = render;
+        true
+    });
+    if !first {
+        return;
+    }
+    match send_request(
+        WorkerRequest::DecompressCache,
+        Callback::from(on_worker_response),
+    ) {
+        Some(_) => CACHE.with_borrow_mut(|cache| cache.in_worker = true),
+        None => schedule(),
+    }
+}
+
+/// Calls `callback` once the worker has sent the cache, if it is still
+/// decompressing it, so that formulas that were missing can be rendered
+pub fn notify_when_ready(callback: Callback<()>) {
+    CACHE.with_borrow_mut(|cache| {
+        if cache.in_worker {
+            cache.waiting.push(callback);
+        }
+    });
+}
+
+/// Returns the text at `range` of the cache. See [`FormulaCache::get`]
+pub fn get_cache(range: (u32, u32)) -> Option<Rc<str>> {
+    get_cache_joined(&[range])
+}
+
+/// Returns the texts at `ranges` of the cache joined together, which is
+/// shared by every later call with the same ranges. Returns [`None`] if the
+/// first range is not available, while other unavailable ranges are skipped
+pub fn get_cache_joined(ranges: &[(u32, u32)]) -> Option<Rc<str>> {
+    CACHE.with_borrow_mut(|cache| cache.text(ranges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decompressed() -> FormulaCache {
+        let mut cache = FormulaCache::default();
+        cache.load(BLOCK_SIZE);
+        cache
+    }
+
+    #[test]
+    fn decompresses_block_to_block_size() {
+        let bytes = decompress_cache(|_, total| assert_eq!(total, BLOCK_SIZE)).unwrap();
+        assert_eq!(bytes.len(), BLOCK_SIZE);
+
+        let cache = decompressed();
+        assert!(cache.is_done());
+        assert_eq!(cache.data.len(), BLOCK_SIZE);
+    }
+
+    #[test]
+    fn every_formula_range_is_valid() {
+        let cache = decompressed();
+        assert!(cache.errors.is_empty(), "{:#?}", cache.errors);
+
+        let tables = [
+            ("CHAMPION_FORMULAS", &CHAMPION_FORMULAS[..]),
+            ("ITEM_FORMULAS", &ITEM_FORMULAS[..]),
+            ("RUNE_FORMULAS", &RUNE_FORMULAS[..]),
+        ];
+        let abilities = ABILITY_FORMULAS
+            .iter()
+            .map(|formulas| ("ABILITY_FORMULAS", &formulas[..]));
+        let attacks = [
+            ("BASIC_ATTACK_OFFSET", &[BASIC_ATTACK_OFFSET][..]),
+            ("CRITICAL_STRIKE_OFFSET", &[CRITICAL_STRIKE_OFFSET][..]),
+        ];
+        for (name, ranges) in tables.into_iter().chain(abilities).chain(attacks) {
+            for &(start, end) in ranges {
+                assert!(start <= end, "{name} has reversed range {start}..{end}");
+                assert!(
+                    end as usize <= BLOCK_SIZE,
+                    "{name} range {start}..{end} is past {BLOCK_SIZE} bytes"
+                );
+                assert!(
+                    cache.slice((start, end)).is_some(),
+                    "{name} range {start}..{end} is not valid UTF-8"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn decompresses_ranges_on_demand() {
+        let mut cache = FormulaCache::default();
+        let range = CHAMPION_FORMULAS[0];
+        let expected = decompressed().slice(range).map(String::from);
+        assert_eq!(cache.get(range).map(String::from), expected);
+        assert!(cache.data.len() >= range.1 as usize);
+    }
+
+    #[test]
+    fn waits_for_the_worker_instead_of_decompressing() {
+        let mut cache = FormulaCache {
+            in_worker: true,
+            ..Default::default()
+        };
+        let range = CHAMPION_FORMULAS[0];
+        assert_eq!(cache.get(range), None);
+        assert!(cache.data.is_empty());
+
+        cache.install(decompress_cache(|_, _| {}).unwrap()).unwrap();
+        cache.in_worker = false;
+        assert!(cache.get(range).is_some());
+    }
+
+    #[test]
+    fn shares_texts_between_calls() {
+        let (main, exc) = (CHAMPION_FORMULAS[0], CHAMPION_FORMULAS[1]);
+        let first = get_cache(main).unwrap();
+        assert!(Rc::ptr_eq(&first, &get_cache(main).unwrap()));
+
+        let joined = get_cache_joined(&[main, exc]).unwrap();
+        assert_eq!(*joined, format!("{first}{}", get_cache(exc).unwrap()));
+    }
+
+    #[test]
+    fn rejects_installed_bytes_of_other_sizes() {
+        let mut cache = FormulaCache::default();
+        assert!(matches!(
+            cache.install(vec![0; BLOCK_SIZE - 1]),
+            Err(CacheError::TooShort(size)) if size == BLOCK_SIZE - 1
+        ));
+        assert!(matches!(
+            cache.install(vec![0; BLOCK_SIZE + 1]),
+            Err(CacheError::TooLong)
+        ));
+        assert!(!cache.is_done());
+
+        let bytes = decompress_cache(|_, _| {}).unwrap();
+        cache.install(bytes).unwrap();
+        assert!(cache.is_done());
+        assert!(cache.errors.is_empty());
     }
-    web_sys::console::time_end();
 }
@@ -0,0 +1,10 @@
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+/// Writes `text` to the system clipboard. Browsers only allow it
+/// in secure contexts and in response to an user interaction
+pub async fn copy_text(text: &str) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or("Window is not available")?;
+    JsFuture::from(window.navigator().clipboard().write_text(text)).await?;
+    Ok(())
+}
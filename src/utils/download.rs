@@ -0,0 +1,88 @@
+use gloo_timers::callback::Timeout;
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use web_sys::{
+    Blob, BlobPropertyBag, CanvasRenderingContext2d, HtmlAnchorElement, HtmlCanvasElement,
+    HtmlImageElement, Url,
+};
+
+/// Time in milliseconds that object URLs are kept alive after clicking them,
+/// since some browsers start the download asynchronously
+const REVOKE_DELAY: u32 = 10_000;
+
+/// Clicks a temporary anchor element that downloads `href` as `filename`
+fn download_href(href: &str, filename: &str) -> Result<(), JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("Document is not available")?;
+    let anchor = document
+        .create_element("a")?
+        .dyn_into::<HtmlAnchorElement>()?;
+    anchor.set_href(href);
+    anchor.set_download(filename);
+    anchor.click();
+    Ok(())
+}
+
+/// Creates a [`Blob`] from a string, with the given MIME type
+fn text_blob(content: &str, mime: &str) -> Result<Blob, JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from_str(content));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    Blob::new_with_str_sequence_and_options(&parts, &options)
+}
+
+/// Downloads a text file named `filename` whose contents are `content`
+pub fn download_text(filename: &str, mime: &str, content: &str) -> Result<(), JsValue> {
+    let url = Url::create_object_url_with_blob(&text_blob(content, mime)?)?;
+    let result = download_href(&url, filename);
+    Timeout::new(REVOKE_DELAY, move || {
+        let _ = Url::revoke_object_url(&url);
+    })
+    .forget();
+    result
+}
+
+/// Rasterizes an SVG document of size `width` x `height` and downloads it
+/// as a PNG image. Drawing happens once the browser finishes decoding the SVG,
+/// so the download starts asynchronously
+pub fn download_svg_as_png(
+    filename: &str,
+    svg: &str,
+    width: u32,
+    height: u32,
+) -> Result<(), JsValue> {
+    let url = Url::create_object_url_with_blob(&text_blob(svg, "image/svg+xml")?)?;
+    let image = HtmlImageElement::new()?;
+    let filename = filename.to_string();
+
+    let onload = {
+        let image = image.clone();
+        let url = url.clone();
+        Closure::once_into_js(move || {
+            let result = (|| -> Result<(), JsValue> {
+                let document = web_sys::window()
+                    .and_then(|window| window.document())
+                    .ok_or("Document is not available")?;
+                let canvas = document
+                    .create_element("canvas")?
+                    .dyn_into::<HtmlCanvasElement>()?;
+                canvas.set_width(width);
+                canvas.set_height(height);
+                let context = canvas
+                    .get_context("2d")?
+                    .ok_or("Canvas 2d context is not available")?
+                    .dyn_into::<CanvasRenderingContext2d>()?;
+                context.draw_image_with_html_image_element(&image, 0.0, 0.0)?;
+                download_href(&canvas.to_data_url_with_type("image/png")?, &filename)
+            })();
+            let _ = Url::revoke_object_url(&url);
+            if let Err(e) = result {
+                web_sys::console::error_1(&format!("Failed to export PNG: {e:?}").into());
+            }
+        })
+    };
+
+    image.set_onload(Some(onload.unchecked_ref()));
+    image.set_src(&url);
+    Ok(())
+}
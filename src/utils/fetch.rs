@@ -5,7 +5,7 @@ use web_sys::AbortSignal;
 
 use crate::utils::BASE_URL;
 
-const CONFIG: Configuration = bincode::config::standard();
+pub const CONFIG: Configuration = bincode::config::standard();
 
 pub async fn post_bytes<T: Decode<()>>(
     url: &str,
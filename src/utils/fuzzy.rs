@@ -0,0 +1,29 @@
+/// Scores how well `query` matches `name`, or returns [`None`] if the characters
+/// of `query` do not appear in order in `name`. Matches at the start of words
+/// and consecutive matches are rewarded, and skipped characters are penalized
+pub fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    let name = name
+        .chars()
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<_>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut last = None;
+    for c in query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+    {
+        let found = position + name[position..].iter().position(|n| *n == c)?;
+        if found == 0 || !name[found - 1].is_alphanumeric() {
+            score += 8;
+        }
+        if last.is_some_and(|last| last + 1 == found) {
+            score += 5;
+        }
+        score -= (found - position) as i32;
+        last = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
@@ -0,0 +1,316 @@
+//! Everything related to loading the icons of [`ImageType`]. Icons are either
+//! loaded one by one, or cut from one sprite sheet per [`ImageCategory`] when
+//! [`ImageMode::Atlas`] is enabled, which avoids hundreds of requests.
+//!
+//! Each sheet is served at [`ImageCategory::sheet_url`], and is a grid of
+//! [`ATLAS_COLUMNS`] columns of square cells of the same size, filled row by
+//! row. Cells are numbered by [`ImageType::atlas_cell`]: champions, items and
+//! runes by their id, and abilities with five cells per champion in the order
+//! of [`ABILITY_SLOTS`]. Cells without an icon are transparent. The sheets are
+//! built by `scripts/atlas.sh` from the cells listed by [`atlas_layout`]
+use crate::utils::{AbilityKind, BASE_URL, ImageType, storage, variants};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+};
+use tutorlolv2_gen::{ChampionId, ItemId, RuneId};
+use wasm_bindgen::{JsCast, closure::Closure};
+use web_sys::HtmlImageElement;
+use yew::Callback;
+
+const STORAGE_KEY: &str = "image_mode";
+
+/// Number of icons in each row of a sprite sheet
+pub const ATLAS_COLUMNS: usize = 32;
+
+/// Order of the abilities of a champion in the abilities sheet, where every
+/// champion takes five consecutive cells
+pub const ABILITY_SLOTS: [char; 5] = ['P', 'Q', 'W', 'E', 'R'];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ImageMode {
+    /// Each icon is loaded from its own file
+    #[default]
+    Individual,
+    /// Icons are cut from the sprite sheet of their category
+    Atlas,
+}
+
+/// Load state of a sprite sheet, which is requested once for all of its icons
+enum SheetState {
+    /// Holds the callbacks to call if the sheet fails to load
+    Loading(Vec<Callback<()>>),
+    Loaded,
+    Failed,
+}
+
+thread_local! {
+    static MODE: Cell<Option<ImageMode>> = const { Cell::new(None) };
+    static PRELOADED: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+    static SHEETS: RefCell<HashMap<ImageCategory, SheetState>> = RefCell::new(HashMap::new());
+}
+
+impl ImageMode {
+    /// Mode saved in the local storage, read only once since it can only
+    /// change through [`Self::save`], which reloads the page
+    pub fn current() -> Self {
+        MODE.with(|mode| {
+            if let Some(value) = mode.get() {
+                return value;
+            }
+            let value = match storage::load(STORAGE_KEY).as_deref() {
+                Some("atlas") => Self::Atlas,
+                _ => Self::Individual,
+            };
+            mode.set(Some(value));
+            value
+        })
+    }
+
+    /// Saves `self` and reloads the page, so every image uses the new mode
+    pub fn save(self) {
+        let value = match self {
+            Self::Individual => "individual",
+            Self::Atlas => "atlas",
+        };
+        storage::save(STORAGE_KEY, value);
+        if let Some(window) = web_sys::window() {
+            let _ = window.location().reload();
+        }
+    }
+}
+
+/// Groups of icons that share a sprite sheet
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ImageCategory {
+    Abilities,
+    Champions,
+    Items,
+    Runes,
+}
+
+impl ImageCategory {
+    pub const ALL: [Self; 4] = [Self::Abilities, Self::Champions, Self::Items, Self::Runes];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Abilities => "abilities",
+            Self::Champions => "champions",
+            Self::Items => "items",
+            Self::Runes => "runes",
+        }
+    }
+
+    pub fn sheet_url(self) -> String {
+        format!("{BASE_URL}/img/atlas/{}.avif", self.name())
+    }
+
+    /// Number of icons in the sheet, including the empty ones at the end
+    const fn len(self) -> usize {
+        match self {
+            Self::Abilities => ChampionId::VARIANTS as usize * ABILITY_SLOTS.len(),
+            Self::Champions => ChampionId::VARIANTS as usize,
+            Self::Items => ItemId::VARIANTS as usize,
+            Self::Runes => RuneId::VARIANTS as usize,
+        }
+    }
+
+    pub const fn rows(self) -> usize {
+        self.len().div_ceil(ATLAS_COLUMNS)
+    }
+}
+
+/// Records the outcome of the request of a sheet, calling the callbacks that
+/// were waiting for it if it failed
+fn settle_sheet(category: ImageCategory, state: SheetState) {
+    let failed = matches!(state, SheetState::Failed);
+    let previous = SHEETS.with_borrow_mut(|sheets| sheets.insert(category, state));
+    if failed && let Some(SheetState::Loading(callbacks)) = previous {
+        for callback in callbacks {
+            callback.emit(());
+        }
+    }
+}
+
+fn request_sheet(category: ImageCategory) {
+    let Ok(element) = HtmlImageElement::new() else {
+        return;
+    };
+    let onload = Closure::once_into_js(move || settle_sheet(category, SheetState::Loaded));
+    let onerror = Closure::once_into_js(move || settle_sheet(category, SheetState::Failed));
+    element.set_onload(Some(onload.unchecked_ref()));
+    element.set_onerror(Some(onerror.unchecked_ref()));
+    element.set_src(&category.sheet_url());
+}
+
+/// Returns false if the sheet of `category` failed to load, in which case its
+/// icons are loaded one by one instead. Background images have no error event,
+/// so the first call requests the sheet with a detached image, and `on_fail`
+/// is called if that request fails after this call
+pub fn sheet_available(category: ImageCategory, on_fail: &Callback<()>) -> bool {
+    let (available, first) = SHEETS.with_borrow_mut(|sheets| match sheets.get_mut(&category) {
+        Some(SheetState::Loading(callbacks)) => {
+            callbacks.push(on_fail.clone());
+            (true, false)
+        }
+        Some(SheetState::Loaded) => (true, false),
+        Some(SheetState::Failed) => (false, false),
+        None => {
+            sheets.insert(category, SheetState::Loading(vec![on_fail.clone()]));
+            (true, true)
+        }
+    });
+    if first {
+        request_sheet(category);
+    }
+    available
+}
+
+/// Position of an icon in the sprite sheet of its category
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasCell {
+    pub category: ImageCategory,
+    pub index: usize,
+}
+
+impl AtlasCell {
+    /// Inline style that displays this cell as the background of an element,
+    /// using percentages so that it works for any size of the element
+    pub fn style(&self) -> String {
+        let percent = |position: usize, count: usize| match count {
+            0 | 1 => 0.0,
+            _ => position as f64 * 100.0 / (count - 1) as f64,
+        };
+        let rows = self.category.rows();
+        format!(
+            "background-image: url('{}'); background-size: {}% {}%; background-position: {}% {}%",
+            self.category.sheet_url(),
+            ATLAS_COLUMNS * 100,
+            rows * 100,
+            percent(self.index % ATLAS_COLUMNS, ATLAS_COLUMNS),
+            percent(self.index / ATLAS_COLUMNS, rows),
+        )
+    }
+}
+
+impl ImageType {
+    /// Cell of this icon in a sprite sheet, or [`None`] for the few icons
+    /// that do not belong to any category
+    pub fn atlas_cell(&self) -> Option<AtlasCell> {
+        let (category, index) = match *self {
+            ImageType::Ability(champion_id, kind) => {
+                let slot = ABILITY_SLOTS.iter().position(|c| *c == kind.as_char())?;
+                (
+                    ImageCategory::Abilities,
+                    champion_id as usize * ABILITY_SLOTS.len() + slot,
+                )
+            }
+            ImageType::Champion(champion_id) => (ImageCategory::Champions, champion_id as usize),
+            ImageType::Item(item_id) => (ImageCategory::Items, item_id as usize),
+            ImageType::Rune(rune_id) => (ImageCategory::Runes, rune_id as usize),
+            ImageType::BasicAttack | ImageType::CritStrike => return None,
+        };
+        Some(AtlasCell { category, index })
+    }
+
+    /// Text displayed when the icon fails to load, made of the initials of [`Self::name`]
+    pub fn initials(&self) -> String {
+        self.name()
+            .split_whitespace()
+            .filter_map(|word| word.chars().next())
+            .take(2)
+            .collect()
+    }
+}
+
+/// Starts downloading every image of `images` that was not requested before,
+/// so they are cached by the time they are displayed. In atlas mode only the
+/// sprite sheets are downloaded, unless they failed to load
+pub fn preload(images: impl IntoIterator<Item = ImageType>) {
+    let mode = ImageMode::current();
+    PRELOADED.with_borrow_mut(|preloaded| {
+        for image in images {
+            let url = match (mode, image.atlas_cell()) {
+                (ImageMode::Atlas, Some(cell))
+                    if sheet_available(cell.category, &Callback::noop()) =>
+                {
+                    continue;
+                }
+                _ => image.url(),
+            };
+            if preloaded.contains(&url) {
+                continue;
+            }
+            if let Ok(element) = HtmlImageElement::new() {
+                element.set_src(&url);
+            }
+            preloaded.insert(url);
+        }
+    });
+}
+
+/// Icons of a champion and all of its abilities
+pub fn champion_images(champion_id: ChampionId) -> impl Iterator<Item = ImageType> {
+    let abilities = tutorlolv2_gen::CHAMPION_ABILITIES[champion_id as usize];
+    std::iter::once(ImageType::Champion(champion_id)).chain(
+        abilities.iter().map(move |ability_id| {
+            ImageType::Ability(champion_id, AbilityKind::Normal(*ability_id))
+        }),
+    )
+}
+
+/// Every icon of `category` in the order of the cells of its sprite sheet,
+/// including the empty cells that complete the last row
+pub fn atlas_layout(category: ImageCategory) -> Vec<Option<ImageType>> {
+    let mut cells = vec![None; category.rows() * ATLAS_COLUMNS];
+    let images = variants::<ChampionId>()
+        .flat_map(champion_images)
+        .chain(variants::<ItemId>().map(ImageType::Item))
+        .chain(variants::<RuneId>().map(ImageType::Rune));
+    for image in images {
+        if let Some(cell) = image.atlas_cell()
+            && cell.category == category
+        {
+            cells[cell.index] = Some(image);
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atlas_cells_hold_a_single_icon() {
+        let images = variants::<ChampionId>()
+            .flat_map(champion_images)
+            .chain(variants::<ItemId>().map(ImageType::Item))
+            .chain(variants::<RuneId>().map(ImageType::Rune));
+        let mut urls = HashMap::new();
+        for image in images {
+            let Some(AtlasCell { category, index }) = image.atlas_cell() else {
+                continue;
+            };
+            assert!(index < category.len(), "{image:?} is past its sheet");
+            let url = urls.entry((category, index)).or_insert_with(|| image.url());
+            assert_eq!(*url, image.url(), "{image:?} shares its cell");
+        }
+    }
+
+    #[test]
+    fn atlas_layout_fills_whole_rows() {
+        for category in ImageCategory::ALL {
+            let cells = atlas_layout(category);
+            assert_eq!(cells.len() % ATLAS_COLUMNS, 0);
+            assert!(cells.len() >= category.len());
+            assert!(cells.iter().any(Option::is_some));
+        }
+        let champion = variants::<ChampionId>().next().unwrap();
+        let cells = atlas_layout(ImageCategory::Champions);
+        assert_eq!(
+            cells[champion as usize],
+            Some(ImageType::Champion(champion))
+        );
+    }
+}
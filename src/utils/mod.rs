@@ -1,5 +1,5 @@
-#![allow(static_mut_refs)]
-use crate::utils::cache::CACHE;
+use crate::utils::cache::{get_cache, get_cache_joined};
+use std::rc::Rc;
 use tutorlolv2_gen::{
     ABILITY_FORMULAS, AbilityId, BASIC_ATTACK_OFFSET, CHAMPION_ABILITIES, CHAMPION_FORMULAS,
     CRITICAL_STRIKE_OFFSET, ChampionId, ITEM_FORMULAS, ITEM_ID_TO_RIOT_ID, ItemId, MergeData,
@@ -8,11 +8,17 @@ use tutorlolv2_gen::{
 use yew::prelude::*;
 
 pub mod cache;
+pub mod clipboard;
+pub mod download;
 pub mod fetch;
+pub mod fuzzy;
+pub mod images;
+pub mod offline;
+pub mod storage;
 
 pub const BASE_URL: &str = "http://localhost:8082";
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AbilityKind {
     Alias(MergeData),
     Normal(AbilityId),
@@ -40,7 +46,7 @@ impl From<AbilityId> for AbilityKind {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ImageType {
     Ability(ChampionId, AbilityKind),
     Champion(ChampionId),
@@ -70,7 +76,10 @@ impl ImageType {
         }
     }
 
-    pub fn offset(&self) -> (String, Option<String>) {
+    /// Ranges of the formula of this image in the formula cache. Merged
+    /// abilities also return the range of the ability that holds their
+    /// maximum damage, which is displayed after the first one
+    pub fn offset(&self) -> ((u32, u32), Option<(u32, u32)>) {
         let mut tuple_exc = None;
         let tuple_main = match self {
             ImageType::Ability(champion_id, kind) => {
@@ -94,18 +103,35 @@ impl ImageType {
             ImageType::CritStrike => CRITICAL_STRIKE_OFFSET,
         };
 
-        let encode = |tuple| {
-            let (start, end) = tuple;
-            (start as u64 * (1 << 23) + end as u64).to_string()
-        };
+        (tuple_main, tuple_exc)
+    }
 
-        (
-            encode(tuple_main),
-            match tuple_exc {
-                Some(tuple) => Some(encode(tuple)),
-                None => None,
-            },
-        )
+    /// HTML of the formula of this image, or [`None`] if it is not in the cache
+    pub fn docs(&self) -> Option<Rc<str>> {
+        match self.offset() {
+            (main, Some(exc)) => get_cache_joined(&[main, exc]),
+            (main, None) => get_cache(main),
+        }
+    }
+
+    /// Human readable name of what this image represents. Abilities are
+    /// prefixed with the name of their champion, such as `Ahri Q`
+    pub fn name(&self) -> String {
+        match self {
+            ImageType::Ability(champion_id, kind) => {
+                let ability_id = kind.ability_id();
+                let char = ability_id.as_char();
+                match ability_id.ability_name().display() {
+                    Some(name) => format!("{} {char} {name}", champion_id.name()),
+                    None => format!("{} {char}", champion_id.name()),
+                }
+            }
+            ImageType::Champion(champion_id) => champion_id.name().to_string(),
+            ImageType::Item(item_id) => item_id.name().to_string(),
+            ImageType::Rune(rune_id) => rune_id.name().to_string(),
+            ImageType::BasicAttack => String::from("Basic Attack"),
+            ImageType::CritStrike => String::from("Critical Strike"),
+        }
     }
 
     pub fn url(&self) -> String {
@@ -137,6 +163,9 @@ macro_rules! impl_base {
             pastey::paste! {
                 impl EnumCast for $ty {
                     const FORMULAS: &[(u32, u32)] = &tutorlolv2_gen::[<$ty:replace("Id", ""):upper _FORMULAS>];
+                    fn name(&self) -> &'static str {
+                        (*self).name()
+                    }
                 }
 
                 impl From<$ty> for ImageType {
@@ -159,15 +188,17 @@ impl_base!(ChampionId, RuneId, ItemId);
 
 pub trait EnumCast: PartialEq + Copy + Into<ImageType> + Into<usize> + TryFrom<usize> {
     const FORMULAS: &[(u32, u32)];
+    fn name(&self) -> &'static str;
     fn docs(&self) -> Html {
         let offset: usize = (*self).into();
-        Html::from_html_unchecked(get_cache(Self::FORMULAS[offset]).into())
+        Html::from_html_unchecked(get_cache(Self::FORMULAS[offset]).unwrap_or_default().into())
     }
     fn image_type(&self) -> ImageType {
         (*self).into()
     }
 }
 
-fn get_cache((i, j): (u32, u32)) -> &'static str {
-    unsafe { core::str::from_utf8_unchecked(CACHE.get_unchecked(i as usize..j as usize)) }
+/// Iterates over every variant of an [`EnumCast`] type
+pub fn variants<T: EnumCast>() -> impl Iterator<Item = T> {
+    (0..T::FORMULAS.len()).filter_map(|i| T::try_from(i).ok())
 }
@@ -0,0 +1,276 @@
+//! Offline mode, which registers the service worker in `public/sw.js` and sends
+//! it every asset of the app to precache: the page, the wasm binaries of the
+//! app and of its worker, styles, fonts and every icon. Caches are versioned by [`version`], a hash of the
+//! names of the built files, so every new build replaces the cached assets
+use crate::utils::{
+    ImageType,
+    images::{ImageCategory, champion_images},
+    storage, variants,
+};
+use js_sys::{Array, Object, Reflect};
+use std::cell::RefCell;
+use tutorlolv2_gen::{ChampionId, ItemId, RuneId};
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use wasm_bindgen_futures::{JsFuture, spawn_local};
+use web_sys::{Element, MessageEvent, ServiceWorkerContainer, ServiceWorkerRegistration};
+use yew::Callback;
+
+const STORAGE_KEY: &str = "offline";
+const SERVICE_WORKER_URL: &str = "./sw.js";
+
+/// Script generated by Trunk that loads the `worker` binary. It keeps the same
+/// name across builds, so the service worker requests it from the network first
+const WORKER_LOADER_URL: &str = "./worker_loader.js";
+
+/// Files that are not referenced by the page itself, such as the font that is
+/// loaded from the CSS and the icons listed in the manifest
+const EXTRA_ASSETS: [&str; 4] = [
+    "./Oxanium-VariableFont_wght.ttf",
+    "./icon-192.png",
+    "./icon-512.png",
+    WORKER_LOADER_URL,
+];
+
+/// Messages sent back by the service worker
+#[derive(Clone, Debug, PartialEq)]
+pub enum OfflineEvent {
+    Progress {
+        done: u32,
+        total: u32,
+    },
+    /// Every asset was requested, holding the number of failed requests
+    Ready {
+        failed: u32,
+    },
+    /// Every cache was deleted and the service worker was unregistered
+    Cleared,
+}
+
+thread_local! {
+    static LISTENER: RefCell<Option<Closure<dyn Fn(MessageEvent)>>> = const { RefCell::new(None) };
+}
+
+/// Whether the offline mode was enabled, which is saved in the local storage
+pub fn is_enabled() -> bool {
+    storage::load(STORAGE_KEY).as_deref() == Some("enabled")
+}
+
+/// Service workers are only available in secure contexts, so this is [`None`]
+/// when the page is served over plain HTTP from another machine
+fn container() -> Option<ServiceWorkerContainer> {
+    let navigator = web_sys::window()?.navigator();
+    Reflect::has(&navigator, &"serviceWorker".into())
+        .unwrap_or(false)
+        .then(|| navigator.service_worker())
+}
+
+pub fn is_supported() -> bool {
+    container().is_some()
+}
+
+/// Version of the crate, followed by a FNV-1a hash of `shell_urls`. Trunk
+/// names the built files after a hash of their contents, and the generated
+/// data is compiled into the wasm binary, so any change to the code or the
+/// data changes the version without hashing the data at runtime
+fn version(shell_urls: &[String]) -> String {
+    let hash = shell_urls
+        .iter()
+        .flat_map(|url| url.bytes().chain([0]))
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    format!("{}-{hash:016x}", env!("CARGO_PKG_VERSION"))
+}
+
+/// URLs of the page and of every file it references, as emitted by Trunk.
+/// Their names hold a hash of their contents
+fn shell_urls() -> Vec<String> {
+    let mut urls = vec!["./".to_string()];
+    urls.extend(EXTRA_ASSETS.iter().map(|url| url.to_string()));
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return urls;
+    };
+    if let Ok(nodes) = document.query_selector_all("link[href], script[src]") {
+        for i in 0..nodes.length() {
+            let Some(element) = nodes
+                .get(i)
+                .and_then(|node| node.dyn_into::<Element>().ok())
+            else {
+                continue;
+            };
+            if let Some(url) = element
+                .get_attribute("href")
+                .or_else(|| element.get_attribute("src"))
+            {
+                urls.push(url);
+            }
+        }
+    }
+    urls
+}
+
+/// URLs of the script and binary of the worker, which are only referenced by
+/// its loader. The loader is a single line of the form
+/// `importScripts("/worker-<hash>.js");wasm_bindgen("/worker-<hash>_bg.wasm");`
+fn loader_urls(loader: &str) -> Vec<String> {
+    loader
+        .split('"')
+        .skip(1)
+        .step_by(2)
+        .filter(|url| url.ends_with(".js") || url.ends_with(".wasm"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Fetches the worker loader to find the files of the worker bundle. Returns
+/// an empty list if it can't be fetched, so the remaining assets are still cached
+async fn worker_urls() -> Vec<String> {
+    let response = match gloo_net::http::Request::get(WORKER_LOADER_URL).send().await {
+        Ok(response) if response.ok() => response,
+        Ok(response) => {
+            web_sys::console::error_1(
+                &format!("Failed to fetch worker loader: {}", response.status()).into(),
+            );
+            return Vec::new();
+        }
+        Err(e) => {
+            web_sys::console::error_1(&format!("Failed to fetch worker loader: {e}").into());
+            return Vec::new();
+        }
+    };
+    match response.text().await {
+        Ok(loader) => loader_urls(&loader),
+        Err(e) => {
+            web_sys::console::error_1(&format!("Failed to read worker loader: {e}").into());
+            Vec::new()
+        }
+    }
+}
+
+/// Every icon of the app, along with the sprite sheets so that both image
+/// modes work offline
+fn image_urls() -> Vec<String> {
+    let images = variants::<ChampionId>()
+        .flat_map(champion_images)
+        .chain(variants::<ItemId>().map(ImageType::Item))
+        .chain(variants::<RuneId>().map(ImageType::Rune))
+        .chain([ImageType::BasicAttack, ImageType::CritStrike]);
+    let sheets = [
+        ImageCategory::Abilities,
+        ImageCategory::Champions,
+        ImageCategory::Items,
+        ImageCategory::Runes,
+    ];
+    images
+        .map(|image| image.url())
+        .chain(sheets.map(ImageCategory::sheet_url))
+        .collect()
+}
+
+fn message(kind: &str, fields: &[(&str, JsValue)]) -> JsValue {
+    let object = Object::new();
+    let _ = Reflect::set(&object, &"type".into(), &kind.into());
+    for (key, value) in fields {
+        let _ = Reflect::set(&object, &(*key).into(), value);
+    }
+    object.into()
+}
+
+fn parse_event(data: &JsValue) -> Option<OfflineEvent> {
+    let field = |key: &str| {
+        Reflect::get(data, &key.into())
+            .ok()
+            .and_then(|value| value.as_f64())
+            .unwrap_or_default() as u32
+    };
+    let kind = Reflect::get(data, &"type".into()).ok()?.as_string()?;
+    match kind.as_str() {
+        "progress" => Some(OfflineEvent::Progress {
+            done: field("done"),
+            total: field("total"),
+        }),
+        "ready" => Some(OfflineEvent::Ready {
+            failed: field("failed"),
+        }),
+        "cleared" => Some(OfflineEvent::Cleared),
+        _ => None,
+    }
+}
+
+/// Forwards the messages of the service worker to `callback`, replacing the
+/// previous listener
+pub fn listen(callback: Callback<OfflineEvent>) {
+    let Some(container) = container() else {
+        return;
+    };
+    let listener = Closure::<dyn Fn(MessageEvent)>::new(move |e: MessageEvent| {
+        if let Some(event) = parse_event(&e.data()) {
+            callback.emit(event);
+        }
+    });
+    container.set_onmessage(Some(listener.as_ref().unchecked_ref()));
+    LISTENER.set(Some(listener));
+}
+
+/// Registers the service worker and waits until it is active, then sends it `message`
+async fn post(message: JsValue) -> Result<(), JsValue> {
+    let container = container().ok_or("Service workers are not supported")?;
+    JsFuture::from(container.register(SERVICE_WORKER_URL)).await?;
+    let registration: ServiceWorkerRegistration =
+        JsFuture::from(container.ready()?).await?.unchecked_into();
+    let worker = registration
+        .active()
+        .ok_or("The service worker is not active")?;
+    worker.post_message(&message)
+}
+
+/// Saves the offline mode and requests every asset of the current version.
+/// Assets that are cached already are not requested again, so this is also
+/// called at startup to cache new versions of the data
+pub fn enable() {
+    storage::save(STORAGE_KEY, "enabled");
+    spawn_local(async move {
+        // The names of the worker bundle are part of the version, since the
+        // worker can change while the files of the page do not
+        let mut shell_urls = shell_urls();
+        shell_urls.extend(worker_urls().await);
+        let version = version(&shell_urls);
+        let urls = shell_urls
+            .into_iter()
+            .chain(image_urls())
+            .map(JsValue::from)
+            .collect::<Array>();
+        let message = message(
+            "precache",
+            &[("version", version.into()), ("urls", urls.into())],
+        );
+        if let Err(e) = post(message).await {
+            web_sys::console::error_1(&format!("Failed to enable offline mode: {e:?}").into());
+        }
+    });
+}
+
+/// Deletes every cached asset and unregisters the service worker
+pub fn disable() {
+    storage::save(STORAGE_KEY, "disabled");
+    spawn_local(async move {
+        if let Err(e) = post(message("clear", &[])).await {
+            web_sys::console::error_1(&format!("Failed to disable offline mode: {e:?}").into());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_worker_bundle_in_loader() {
+        let loader = r#"importScripts("/worker-1a2b.js");wasm_bindgen("/worker-1a2b_bg.wasm");"#;
+        assert_eq!(
+            loader_urls(loader),
+            ["/worker-1a2b.js", "/worker-1a2b_bg.wasm"]
+        );
+        assert!(loader_urls("").is_empty());
+    }
+}
@@ -0,0 +1,19 @@
+use web_sys::Storage;
+
+fn local_storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Reads a value from the local storage. Returns [`None`] if it was never
+/// saved or if the storage is not available, such as in private browsing
+pub fn load(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok()?
+}
+
+/// Saves a value in the local storage, ignoring failures since
+/// every stored value can be recreated from its default
+pub fn save(key: &str, value: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(key, value);
+    }
+}
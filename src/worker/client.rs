@@ -0,0 +1,142 @@
+use crate::worker::{Envelope, RequestId, WorkerRequest, WorkerResponse, runner};
+use js_sys::Uint8Array;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+use wasm_bindgen::{JsCast, closure::Closure};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{MessageEvent, Worker};
+use yew::Callback;
+
+/// Script generated by Trunk that loads the `worker` binary
+const WORKER_URL: &str = "./worker_loader.js";
+
+/// Main thread side of the worker, which posts the requests to it
+struct WorkerClient {
+    worker: Worker,
+    _listener: Closure<dyn Fn(MessageEvent)>,
+}
+
+thread_local! {
+    /// [`None`] if the worker failed to start, in which case requests run on
+    /// the main thread instead
+    static CLIENT: Option<WorkerClient> = WorkerClient::spawn();
+    static NEXT_ID: Cell<RequestId> = const { Cell::new(0) };
+    /// Callbacks of the requests that may still get responses
+    static HANDLERS: RefCell<HashMap<RequestId, Callback<WorkerResponse>>> =
+        RefCell::new(HashMap::new());
+}
+
+impl WorkerClient {
+    fn spawn() -> Option<Self> {
+        let worker = match Worker::new(WORKER_URL) {
+            Ok(worker) => worker,
+            Err(e) => {
+                web_sys::console::error_1(
+                    &format!("Failed to spawn worker, running on the main thread: {e:?}").into(),
+                );
+                return None;
+            }
+        };
+        let listener = Closure::<dyn Fn(MessageEvent)>::new(on_message);
+        worker.set_onmessage(Some(listener.as_ref().unchecked_ref()));
+        Some(Self {
+            worker,
+            _listener: listener,
+        })
+    }
+
+    fn post(&self, envelope: Envelope<WorkerRequest>) -> bool {
+        let sent = envelope
+            .encode()
+            .map_err(|e| format!("{e:?}"))
+            .and_then(|bytes| {
+                self.worker
+                    .post_message(&Uint8Array::from(bytes.as_slice()))
+                    .map_err(|e| format!("{e:?}"))
+            });
+        if let Err(e) = &sent {
+            web_sys::console::error_1(&format!("Failed to send request to worker: {e}").into());
+        }
+        sent.is_ok()
+    }
+}
+
+fn on_message(event: MessageEvent) {
+    let bytes = Uint8Array::new(&event.data()).to_vec();
+    match Envelope::<WorkerResponse>::decode(&bytes) {
+        Ok(Envelope { id, body }) => dispatch(id, body),
+        Err(e) => web_sys::console::error_1(&format!("Failed to decode response: {e:?}").into()),
+    }
+}
+
+/// Calls the callback of the request `id` with `response`
+fn dispatch(id: RequestId, response: WorkerResponse) {
+    // The handler is taken out while it runs, so it may send other requests
+    let Some(handler) = HANDLERS.with_borrow_mut(|handlers| handlers.remove(&id)) else {
+        return;
+    };
+    let is_final = response.is_final();
+    handler.emit(response);
+    if !is_final {
+        HANDLERS.with_borrow_mut(|handlers| handlers.insert(id, handler));
+    }
+}
+
+/// Runs `request` on the main thread, for when the worker is not available.
+/// Decompressing the cache never yields, so it is not run here, and the cache
+/// decompresses itself in idle callbacks instead
+fn run_locally(id: RequestId, request: WorkerRequest) -> bool {
+    if matches!(request, WorkerRequest::DecompressCache) {
+        return false;
+    }
+    spawn_local(async move {
+        let on_progress = move |done, total| dispatch(id, WorkerResponse::Progress { done, total });
+        let response = runner::run(id, request, on_progress).await;
+        dispatch(id, response);
+    });
+    true
+}
+
+/// Sends `request` to the worker, and calls `on_response` with every response
+/// to it. If the worker is not available, the request runs on the main thread
+/// instead. Returns [`None`] if the request could not be sent or can only run
+/// in the worker, in which case `on_response` is never called
+pub fn send_request(
+    request: WorkerRequest,
+    on_response: Callback<WorkerResponse>,
+) -> Option<RequestId> {
+    let id = NEXT_ID.get();
+    NEXT_ID.set(id.wrapping_add(1));
+    HANDLERS.with_borrow_mut(|handlers| handlers.insert(id, on_response));
+    let sent = CLIENT.with(|client| match client {
+        Some(client) => client.post(Envelope { id, body: request }),
+        None => run_locally(id, request),
+    });
+    if !sent {
+        HANDLERS.with_borrow_mut(|handlers| handlers.remove(&id));
+        return None;
+    }
+    Some(id)
+}
+
+/// Asks the worker to stop the request `id`. Responses that arrive after
+/// this call are ignored, so the callback of the request is not called again
+pub fn cancel_request(id: RequestId) {
+    if HANDLERS
+        .with_borrow_mut(|handlers| handlers.remove(&id))
+        .is_none()
+    {
+        return;
+    }
+    CLIENT.with(|client| match client {
+        Some(client) => {
+            client.post(Envelope {
+                id,
+                body: WorkerRequest::Cancel(id),
+            });
+        }
+        None => runner::cancel(id),
+    });
+}
@@ -0,0 +1,74 @@
+//! Work that is too slow for the main thread runs in a Web Worker, started
+//! from the `worker` binary. Both sides exchange [`Envelope`]s encoded with
+//! bincode, where the worker answers each [`WorkerRequest`] with any number of
+//! [`WorkerResponse::Progress`] followed by a single final response. If the
+//! worker can not be started, [`client`] runs the requests on the main thread
+use crate::{
+    calculator::{
+        InputGame,
+        commands::Suggestion,
+        sweep::{SweepAxis, SweepResult},
+    },
+    utils::fetch::CONFIG,
+};
+use bincode::{Decode, Encode};
+
+pub mod client;
+pub mod runner;
+
+/// Identifies a request, and every response sent back for it
+pub type RequestId = u32;
+
+#[derive(Clone, Debug, Decode, Encode, PartialEq)]
+pub enum WorkerRequest {
+    /// Decompresses the whole formula cache, answered with [`WorkerResponse::Cache`]
+    DecompressCache,
+    /// Runs [`crate::calculator::sweep::run_sweep`], answered with [`WorkerResponse::Sweep`]
+    RunSweep {
+        input: InputGame,
+        axis: SweepAxis,
+        enemy_index: usize,
+    },
+    /// Matches a query of the command palette against every command, champion,
+    /// item and rune, answered with [`WorkerResponse::Suggestions`]
+    Suggest(String),
+    /// Stops the request with the given id, which is then answered with
+    /// [`WorkerResponse::Cancelled`]. This request has no response of its own
+    Cancel(RequestId),
+}
+
+#[derive(Clone, Debug, Decode, Encode, PartialEq)]
+pub enum WorkerResponse {
+    Progress { done: usize, total: usize },
+    Cache(Vec<u8>),
+    Sweep(SweepResult),
+    Suggestions(Vec<Suggestion>),
+    Cancelled,
+    Error(String),
+}
+
+impl WorkerResponse {
+    /// Returns true if no other response will be sent for the same request
+    pub const fn is_final(&self) -> bool {
+        !matches!(self, Self::Progress { .. })
+    }
+}
+
+/// Message sent between the main thread and the worker
+#[derive(Clone, Debug, Decode, Encode, PartialEq)]
+pub struct Envelope<T> {
+    pub id: RequestId,
+    pub body: T,
+}
+
+impl<T: Encode> Envelope<T> {
+    pub fn encode(&self) -> Result<Vec<u8>, bincode::error::EncodeError> {
+        bincode::encode_to_vec(self, CONFIG)
+    }
+}
+
+impl<T: Decode<()>> Envelope<T> {
+    pub fn decode(bytes: &[u8]) -> Result<Self, bincode::error::DecodeError> {
+        bincode::decode_from_slice(bytes, CONFIG).map(|(envelope, _)| envelope)
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{
+    calculator::{commands::suggestions, sweep::run_sweep},
+    utils::cache::decompress_cache,
+    worker::{Envelope, RequestId, WorkerRequest, WorkerResponse},
+};
+use js_sys::Uint8Array;
+use std::{cell::RefCell, collections::HashMap, error::Error};
+use wasm_bindgen::{JsCast, closure::Closure};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{AbortController, AbortSignal, DedicatedWorkerGlobalScope, MessageEvent};
+
+thread_local! {
+    /// Controllers of the requests that are still running, aborted by [`WorkerRequest::Cancel`]
+    static RUNNING: RefCell<HashMap<RequestId, AbortController>> = RefCell::new(HashMap::new());
+}
+
+fn scope() -> DedicatedWorkerGlobalScope {
+    js_sys::global().unchecked_into()
+}
+
+fn respond(id: RequestId, body: WorkerResponse) {
+    match (Envelope { id, body }).encode() {
+        Ok(bytes) => {
+            let _ = scope().post_message(&Uint8Array::from(bytes.as_slice()));
+        }
+        Err(e) => web_sys::console::error_1(&format!("Failed to encode response: {e:?}").into()),
+    }
+}
+
+/// Converts the result of an abortable request, answering with
+/// [`WorkerResponse::Cancelled`] if it failed because it was aborted
+fn finish<T>(
+    result: Result<T, Box<dyn Error>>,
+    signal: Option<AbortSignal>,
+    map: fn(T) -> WorkerResponse,
+) -> WorkerResponse {
+    match result {
+        Ok(value) => map(value),
+        Err(_) if signal.is_some_and(|signal| signal.aborted()) => WorkerResponse::Cancelled,
+        Err(e) => WorkerResponse::Error(e.to_string()),
+    }
+}
+
+/// Runs `request`, calling `on_progress` with the number of completed steps
+/// and the total. Also used by [`crate::worker::client`] to run requests on the
+/// main thread when the worker is not available
+pub async fn run(
+    id: RequestId,
+    request: WorkerRequest,
+    on_progress: impl Fn(usize, usize),
+) -> WorkerResponse {
+    let controller = AbortController::new().ok();
+    let signal = controller.as_ref().map(|c| c.signal());
+    if let Some(controller) = controller {
+        RUNNING.with_borrow_mut(|running| running.insert(id, controller));
+    }
+
+    let response = match request {
+        // Decompression runs without yielding, so it can not be cancelled
+        WorkerRequest::DecompressCache => match decompress_cache(on_progress) {
+            Ok(bytes) => WorkerResponse::Cache(bytes),
+            Err(e) => WorkerResponse::Error(e.to_string()),
+        },
+        WorkerRequest::RunSweep {
+            input,
+            axis,
+            enemy_index,
+        } => finish(
+            run_sweep(input, axis, enemy_index, signal.clone(), on_progress).await,
+            signal,
+            WorkerResponse::Sweep,
+        ),
+        WorkerRequest::Suggest(query) => WorkerResponse::Suggestions(suggestions(&query)),
+        WorkerRequest::Cancel(_) => WorkerResponse::Error(String::from("Cancel has no response")),
+    };
+
+    RUNNING.with_borrow_mut(|running| running.remove(&id));
+    response
+}
+
+/// Aborts the request `id` if it is still running, so it is answered with
+/// [`WorkerResponse::Cancelled`]
+pub fn cancel(id: RequestId) {
+    if let Some(controller) = RUNNING.with_borrow_mut(|running| running.remove(&id)) {
+        controller.abort();
+    }
+}
+
+fn on_message(event: MessageEvent) {
+    let bytes = Uint8Array::new(&event.data()).to_vec();
+    let Envelope { id, body } = match Envelope::<WorkerRequest>::decode(&bytes) {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            web_sys::console::error_1(&format!("Failed to decode request: {e:?}").into());
+            return;
+        }
+    };
+    match body {
+        WorkerRequest::Cancel(target) => cancel(target),
+        request => spawn_local(async move {
+            let on_progress =
+                move |done, total| respond(id, WorkerResponse::Progress { done, total });
+            let response = run(id, request, on_progress).await;
+            respond(id, response);
+        }),
+    }
+}
+
+/// Entry point of the worker binary, which answers every message sent by
+/// [`crate::worker::client`] until the worker is terminated
+pub fn run_worker() {
+    let listener = Closure::<dyn Fn(MessageEvent)>::new(on_message);
+    scope().set_onmessage(Some(listener.as_ref().unchecked_ref()));
+    listener.forget();
+}